@@ -0,0 +1,633 @@
+// the command line interface. every calculation is a subcommand and the arguments are the given
+// values like in the interactive mode, --name value is the same as name=value:
+//   elek_wider wire --material copper --length 10m --diameter 1.5mm --find R
+//   elek_wider ohm 12V 2A
+//   elek_wider solve "U=12V; R=4k7" I
+// the exit code is 0 for a result, 1 if the values are wrong or there is no result and 2 if the
+// command line itself is wrong
+use std::fmt;
+
+use super::{format_value, parse_value, split_given, Area, Elect, URI};
+use coil::Coil;
+use colour_code::ColourCode;
+use convert::Conversion;
+use decibel::Decibel;
+use divider::Divider;
+use eseries::Preferred;
+use filter::Filter;
+use inductance::Geometry;
+use led::Led;
+use magnetic::MagneticCircuit;
+use monte_carlo::MonteCarlo;
+use passive::Passive;
+use pcb::{Trace, Via};
+use plot::Plot;
+use repl;
+use resistivity;
+use sweep::Sweep;
+use transmission_line::Line;
+use three_phase::{CableDrop, NeutralCurrent, StarDelta, ThreePhase};
+use tolerance::{Calculation, ToleranceCalc};
+use transformer::Transformer;
+use transient::{FirstOrder, SecondOrder};
+
+pub const SUCCESS: i32 = 0;
+pub const CALCULATION_ERROR: i32 = 1;
+pub const USAGE_ERROR: i32 = 2;
+
+// a negative or zero value for wire, ohm or solve is a wrong command line and not a calculation
+// without a result
+const NOT_POSITIVE: &str = "the values of wire, ohm and solve have to be positive";
+
+// name, description and the arguments of every subcommand for the help
+static COMMANDS: [(&str, &str, &str); 30] = [
+    (
+        "wire",
+        "resistance, specific resistance, length or area of a wire",
+        "--material copper|--rho 0.0178ohmxmm2/m --length 10m --diameter 1.5mm|--area 1.5mm2 \
+         --resistance 0.1ohm --temperature 80°C --spacing 5mm --er 2 --find R|rho|l|A|d",
+    ),
+    ("ohm", "Ohm's law, two of U, I and R", "12V 2A | 12V 4k7"),
+    (
+        "solve",
+        "solves for one variable of Ohm's law or the wire resistance",
+        "\"U=12V; R=4k7\" I | \"l=10m; d=1.5mm; copper\" R",
+    ),
+    ("interactive", "variables, results and calculations in a session", "(help inside for the commands)"),
+    (
+        "three-phase",
+        "line and phase values and the power of a three phase system",
+        "star|delta ul=400V il=10A cos=0.85 (uph, iph, p, q, s)",
+    ),
+    ("star-delta", "star delta transformation", "r1= r2= r3= | r12= r23= r31="),
+    ("neutral", "current in the neutral conductor", "i1= i2= i3= (cos1= cos2= cos3=)"),
+    (
+        "cable-drop",
+        "voltage drop and loss of a three phase cable",
+        "i=32A ul=400V cos=0.9 x=0.08ohm/km copper 50m 6mm2",
+    ),
+    (
+        "rc",
+        "charging and discharging of RC and RL circuits",
+        "charge|discharge r=10kohm c=100uF|l=10mH|tau= u=12V u0= uth= t= samples=",
+    ),
+    ("rlc", "step response of a series RLC circuit", "r= l= c= | f0= zeta= u= samples="),
+    ("preferred", "nearest e-series values, pairs and dividers", "r=4k7 ratio= rtotal= series=E24"),
+    (
+        "filter",
+        "passive RC, LC and RLC filters",
+        "lowpass|highpass|bandpass|bandstop rc|lc|rlc fc=1kHz q= bw= r= l= c= f= series=",
+    ),
+    ("colour", "resistor colour codes and smd markings", "red red orange gold | smd=472 | r=4k7 bands=5"),
+    ("passive", "capacitors and inductors, markings and energy", "series|parallel c= l= cap=104 ind= u= i="),
+    ("divider", "loaded voltage divider", "uin=12V uout=5V rl=10kohm|il= iq= k=10 series="),
+    ("led", "series resistors for leds", "u=12V colour=red|uf=2V i=20mA n=3 s= series|parallel|mixed"),
+    (
+        "tolerance",
+        "worst case and RSS tolerance of wire or ohm",
+        "wire|ohm 12V±1% 4.7kohm±5%",
+    ),
+    ("monte-carlo", "monte carlo analysis of wire or ohm", "wire|ohm 12V±1% 4.7kohm±1%σ n= seed= min= max="),
+    (
+        "sweep",
+        "parameter sweep of wire or ohm as table, csv or json",
+        "wire|ohm r=10ohm..1kohm log 5 12V format=table|csv|json",
+    ),
+    (
+        "plot",
+        "ascii and svg plots of a sweep",
+        "wire|ohm t=-40°C..120°C:10°C {copper, iron} 10m 1.5mm2 plot=ascii|svg|both file= log=x|y|xy",
+    ),
+    (
+        "convert",
+        "converts a value to another unit, also AWG and levels like dBm or dBµV",
+        "2.5mm2 to kcmil | 0.0172 Ω·mm²/m to Ω·m | 3 kWh to MJ | 20 AWG to mm | 0dBm to dBµV at 75ohm",
+    ),
+    (
+        "db",
+        "decibels of ratios, levels and chains of gains, losses and cables",
+        "ratio=2 | 10dBm z=50ohm | 0dBm -3dB +20dB loss=6dB a=6.6dB/100m l=25m",
+    ),
+    (
+        "trace",
+        "width or current of a pcb trace after IPC-2221, with resistance, drop and loss",
+        "i=3A|w=1mm dt=10K external|internal oz=1|h=35um l=50mm t=25°C",
+    ),
+    (
+        "via",
+        "resistance and current of vias, with a trace the resistance of the whole path",
+        "d=0.3mm plating=25um board=1.6mm i=3A n= dt=10K (w=1mm l=50mm oz=1 for the trace)",
+    ),
+    (
+        "line",
+        "impedance, delay and loss of coax, twisted pair, microstrip and stripline",
+        "coax di=1mm da=4.6mm | twisted-pair d=0.5mm s=1mm | microstrip|stripline w=0.3mm \
+         height=0.2mm oz=1 er=4.4 tand=0.02 f=100MHz l=10m copper",
+    ),
+    (
+        "lc",
+        "inductance and capacitance of wires, loops, two wire lines, air coils, plates and coax",
+        "wire l=1m a=1.5mm2 | loop loop=100mm d=1mm | two-wire l=1m d=1mm s=5mm er=2 | \
+         solenoid n=20 d=10mm l=20mm | plates a=100mm2 s=0.1mm er=4.4 | coax l=1m di=1mm da=4.6mm",
+    ),
+    (
+        "coil",
+        "turns, layers, wire length, resistance, inductance and mass of a coil on a bobbin",
+        "di=10mm b=20mm d=0.5mm|a=0.2mm2|awg=24 do=0.55mm lt=100uH|n=200|height=5mm copper t=20°C",
+    ),
+    (
+        "transformer",
+        "ratio, currents, core size, winding losses, regulation and efficiency of a transformer",
+        "u1=230V u2=12V|n1= n2=|ratio= rl=2ohm|s=50VA pf=1 steel|ferrite b=1.2T f=50Hz j=3 \
+         ku=0.4 ac=10cm² mlt=0.1m r1= r2= pfe=2W t=75°C square",
+    ),
+    (
+        "magnetic",
+        "reluctance, flux, ampere-turns and inductance of a core with air gaps, Θ = Φ·Rm",
+        "le=100mm ae=100mm2 ferrite|mur=2000 gap=0.5mm n=50 i=1A|theta=50A|b=0.2T|phi=20uWb|rm=",
+    ),
+    ("help", "shows this help or the help of a subcommand", "[subcommand]"),
+];
+
+// the names of the subcommands, for the completion in the interactive mode
+pub fn command_names() -> Vec<&'static str> {
+    COMMANDS.iter().map(|command| command.0).collect()
+}
+
+fn print_usage() {
+    println!("usage: elek_wider <subcommand> [values]");
+    println!("       elek_wider help <subcommand>");
+    println!();
+    println!("the values are given as name=value, --name value or as plain values like 12V.");
+    println!("subcommands:");
+    for command in COMMANDS.iter() {
+        println!("  {:<12} {}", command.0, command.1);
+    }
+}
+
+fn print_help(name: &str) -> i32 {
+    if let Some(command) = COMMANDS.iter().find(|command| command.0 == name) {
+        println!("{}: {}", command.0, command.1);
+        println!("usage: elek_wider {} {}", command.0, command.2);
+        SUCCESS
+    } else {
+        eprintln!("error: subcommand {} not known!", name);
+        USAGE_ERROR
+    }
+}
+
+// the arguments as given values, --name value and --name=value become name=value
+fn given_values(args: &[String]) -> Vec<String> {
+    let mut values: Vec<String> = Vec::new();
+    let mut k = 0;
+    while k < args.len() {
+        if args[k].starts_with("--") {
+            let name = args[k].trim_start_matches('-');
+            if name.contains('=') {
+                values.push(name.to_string());
+            } else if k + 1 < args.len() && !args[k + 1].starts_with("--") {
+                values.push(format!("{}={}", name, args[k + 1]));
+                k += 1;
+            } else {
+                values.push(name.to_string());
+            }
+        } else {
+            values.push(args[k].clone());
+        }
+        k += 1;
+    }
+    values
+}
+
+// the first value of tolerance, monte-carlo, sweep and plot says what is calculated
+fn calculation(values: &mut Vec<String>) -> Result<Calculation, &'static str> {
+    if values.is_empty() {
+        return Err("give me wire or ohm as first value");
+    }
+    match values.remove(0).to_lowercase().as_str() {
+        "wire" | "elect" => Ok(Calculation::Elect),
+        "ohm" | "uri" => Ok(Calculation::Uri),
+        _ => Err("give me wire or ohm as first value"),
+    }
+}
+
+pub fn run(args: Vec<String>) -> i32 {
+    if args.is_empty() {
+        print_usage();
+        return USAGE_ERROR;
+    }
+    let command = args[0].as_str();
+    match command {
+        "help" | "--help" | "-h" => {
+            return if args.len() > 1 {
+                print_help(&args[1])
+            } else {
+                print_usage();
+                SUCCESS
+            };
+        }
+        "--version" | "-V" => {
+            println!("elek_wider {}", env!("CARGO_PKG_VERSION"));
+            return SUCCESS;
+        }
+        _ => {}
+    }
+    if !COMMANDS.iter().any(|known| known.0 == command) {
+        eprintln!("error: subcommand {} not known!", command);
+        print_usage();
+        return USAGE_ERROR;
+    }
+    if args[1..].iter().any(|arg| arg == "--help" || arg == "-h") {
+        return print_help(command);
+    }
+    let mut values = given_values(&args[1..]);
+    let result = match command {
+        "wire" => wire(values),
+        "ohm" => ohm(values),
+        "solve" => {
+            if values.len() < 2 {
+                eprintln!("error: give me the given values and the searched variable!");
+                return USAGE_ERROR;
+            }
+            let searched = values.pop().unwrap_or_default();
+            solve(&values.join(";"), &searched).map(|result| println!("{}", result))
+        }
+        "interactive" => repl::run(),
+        "three-phase" => ThreePhase::new(values).and_then(|mut x| x.calc(true)),
+        "star-delta" => StarDelta::new(values).and_then(|mut x| x.calc(true)),
+        "neutral" => NeutralCurrent::new(values).and_then(|mut x| x.calc(true)),
+        "cable-drop" => CableDrop::new(values).and_then(|mut x| x.calc(true)),
+        "rc" => FirstOrder::new(values).and_then(|mut x| x.calc(true)),
+        "rlc" => SecondOrder::new(values).and_then(|mut x| x.calc(true)),
+        "preferred" => Preferred::new(values).map(|x| x.calc(true)),
+        "filter" => Filter::new(values).and_then(|mut x| x.calc(true)),
+        "colour" => ColourCode::new(values).and_then(|mut x| x.calc(true)),
+        "passive" => Passive::new(values).map(|mut x| x.calc(true)),
+        "divider" => Divider::new(values).and_then(|mut x| x.calc(true)),
+        "led" => Led::new(values).and_then(|mut x| x.calc(true)),
+        "tolerance" => calculation(&mut values)
+            .and_then(|calculation| ToleranceCalc::new(values, calculation))
+            .and_then(|mut x| x.calc(true)),
+        "monte-carlo" => calculation(&mut values)
+            .and_then(|calculation| MonteCarlo::new(values, calculation))
+            .and_then(|mut x| x.calc(true)),
+        "sweep" => calculation(&mut values)
+            .and_then(|calculation| Sweep::new(values, calculation))
+            .and_then(|mut x| x.calc(true)),
+        "convert" => Conversion::new(values).and_then(|mut x| x.calc(true)),
+        "db" => Decibel::new(values).and_then(|mut x| x.calc(true)),
+        "trace" => Trace::new(values).and_then(|mut x| x.calc(true)),
+        "via" => Via::new(values).and_then(|mut x| x.calc(true)),
+        "line" => Line::new(values).and_then(|mut x| x.calc(true)),
+        "lc" => Geometry::new(values).and_then(|mut x| x.calc(true)),
+        "coil" => Coil::new(values).and_then(|mut x| x.calc(true)),
+        "transformer" => Transformer::new(values).and_then(|mut x| x.calc(true)),
+        "magnetic" => MagneticCircuit::new(values).and_then(|mut x| x.calc(true)),
+        "plot" => calculation(&mut values)
+            .and_then(|calculation| Plot::new(values, calculation))
+            .and_then(|mut x| x.calc(true)),
+        // a command in COMMANDS without an arm here
+        _ => {
+            eprintln!("error: subcommand {} not known!", command);
+            print_usage();
+            return USAGE_ERROR;
+        }
+    };
+    match result {
+        Ok(()) => SUCCESS,
+        Err(NOT_POSITIVE) => {
+            eprintln!("error: {}!", NOT_POSITIVE);
+            USAGE_ERROR
+        }
+        Err(error) => {
+            eprintln!("error: {}!", error);
+            CALCULATION_ERROR
+        }
+    }
+}
+
+// a named value as string for Elect or URI. the pre units are resolved here, because Elect and
+// URI only know the base units
+fn elect_or_uri_value(name: &str, value: &str) -> Result<String, &'static str> {
+    let positive = |value: f64| if value > 0f64 { Ok(value) } else { Err(NOT_POSITIVE) };
+    match name {
+        "u" => Ok(format!("{}V", positive(parse_value(value, &["V"])?)?)),
+        "i" => Ok(format!("{}A", positive(parse_value(value, &["A"])?)?)),
+        "r" => Ok(format!("{}ohm", positive(parse_value(value, &["ohm", "Ω"])?)?)),
+        // rho can be given in every form, like 1.72µΩ·cm, 58MS/m or 100%IACS
+        "rho" | "ρ" => match resistivity::parse_rho(value) {
+            Some(rho) => Ok(format!("{}ohmxmm2/m", positive(rho?)?)),
+            None => Ok(format!(
+                "{}ohmxmm2/m",
+                positive(parse_value(value, &["ohmxmm2/m", "Ω·mm²/m", "Ωmm²/m"])?)?
+            )),
+        },
+        // a plain conductivity is taken in MS/m, that is m/(Ω·mm²)
+        "sigma" | "σ" | "kappa" | "κ" => match resistivity::parse_rho(value) {
+            Some(rho) => Ok(format!("{}ohmxmm2/m", rho?)),
+            None => {
+                let sigma = parse_value(value, &["MS/m"])?;
+                if sigma <= 0f64 {
+                    return Err("the conductivity has to be positive");
+                }
+                Ok(format!("{}ohmxmm2/m", 1f64 / sigma))
+            }
+        },
+        "rs" | "sheet" => match resistivity::parse_sheet(value) {
+            Some(sheet) => Ok(format!("{}ohm/sq", sheet?)),
+            None => Ok(format!("{}ohm/sq", parse_value(value, &["ohm", "Ω"])?)),
+        },
+        "h" | "thickness" => Ok(format!("thickness={}mm", resistivity::parse_thickness(value)?)),
+        "l" => Ok(format!("{}m", positive(parse_value(value, &["m"])?)?)),
+        // the return wire and its insulation for the inductance and capacitance
+        "s" => Ok(format!("spacing={}m", parse_value(value, &["m"])?)),
+        "er" | "εr" => Ok(format!("er={}", parse_value(value, &[])?)),
+        "a" => Ok(format!("{}mm2", positive(parse_value(value, &["mm2", "mm²"])?)?)),
+        // 1.5mm is taken as it is, 1.5m would be a thick wire
+        "d" => match parse_value(value, &["mm"]) {
+            Ok(d) => Ok(format!("{}mm", positive(d)?)),
+            Err(_) => Ok(format!("{}mm", positive(parse_value(value, &["m"])? * 1000f64)?)),
+        },
+        "t" => Ok(format!("{}°C", parse_value(value, &["°C", "degC"])?)),
+        "" | "material" | "mat" => Ok(value.to_string()),
+        _ => Err("quantity not known"),
+    }
+}
+
+// the long names of wire as the short ones of solve
+fn wire_name(name: &str) -> &str {
+    match name {
+        "resistance" | "resistor" => "r",
+        "length" => "l",
+        "diameter" => "d",
+        "area" => "a",
+        "temperature" => "t",
+        "conductivity" => "sigma",
+        "sheet-resistance" => "rs",
+        "thickness" => "h",
+        "spacing" => "s",
+        _ => name,
+    }
+}
+
+fn wire(values: Vec<String>) -> Result<(), &'static str> {
+    let mut given: Vec<String> = Vec::new();
+    let mut searched: Option<String> = None;
+    for i in values.iter() {
+        let (name, value) = split_given(i);
+        if name == "find" {
+            // the forms of rho like MS/m keep their case
+            searched = Some(match resistivity::form(&value) {
+                Some(form) => form.to_string(),
+                None => wire_name(&value.to_lowercase()).to_string(),
+            });
+        } else {
+            given.push(elect_or_uri_value(wire_name(&name), &value)?);
+        }
+    }
+    let mut elect = Elect::new(given)?;
+    let index = elect.calc(false);
+    if index < 0 {
+        return Err("give me exactly three of R, rho, l and A or d");
+    }
+    // a searched form of rho like µΩ·cm or MS/m, it can also come from a given material or rho
+    let form = match (&searched, elect.rho) {
+        (Some(searched), Some(rho)) => rho_form(&elect, rho, searched)?,
+        _ => None,
+    };
+    if let Some(searched) = &searched {
+        if elect_index(searched)? != index && form.is_none() {
+            return Err("the searched value is given too");
+        }
+    }
+    elect.print_res(index);
+    if let Some(form) = form {
+        println!("{}", form);
+    }
+    Ok(())
+}
+
+// rho in the searched form or as sheet resistance, None if plain rho is searched
+fn rho_form(elect: &Elect, rho: f64, searched: &str) -> Result<Option<Solution>, &'static str> {
+    if ["r", "ohm", "Ω", "rho", "ρ", "ohmxmm2/m", "l", "m", "a", "mm2", "d", "mm"].contains(&searched) {
+        return Ok(None);
+    }
+    if searched == "rs" || searched == "sheet" {
+        return match elect.thickness {
+            Some(thickness) => Ok(Some(Solution {
+                name: "Rs",
+                value: rho / (thickness * 1000f64),
+                unit: "Ω/sq",
+            })),
+            None => Err("give me the thickness for the sheet resistance"),
+        };
+    }
+    match resistivity::form(searched) {
+        Some(form) => Ok(Some(Solution {
+            name: if resistivity::is_conductivity(form) { "σ" } else { "ρ" },
+            value: resistivity::rho_in(rho, form)?,
+            unit: form,
+        })),
+        None => Err("searched form of rho not known"),
+    }
+}
+
+fn ohm(values: Vec<String>) -> Result<(), &'static str> {
+    let mut given: Vec<String> = Vec::new();
+    for i in values.iter() {
+        let (name, value) = split_given(i);
+        // a plain value is sorted by its unit, without one it is a resistance like 4k7
+        let name = if !name.is_empty() {
+            name
+        } else if value.ends_with('V') {
+            "u".to_string()
+        } else if value.ends_with('A') {
+            "i".to_string()
+        } else {
+            "r".to_string()
+        };
+        given.push(elect_or_uri_value(&name, &value)?);
+    }
+    if given.len() != 2 {
+        return Err("give me two of U, I and R");
+    }
+    let mut uri = URI::new(given)?;
+    // URI prints nothing useful for values it can't calculate, so we check first
+    let mut check = uri.clone();
+    check.calc(false);
+    if check.u.is_none() || check.i.is_none() || check.r.is_none() {
+        return Err("give me two positive values of U, I and R");
+    }
+    uri.calc(true);
+    Ok(())
+}
+
+// the index of the result of Elect::calc
+fn elect_index(searched: &str) -> Result<i8, &'static str> {
+    match searched {
+        "r" | "ohm" | "Ω" => Ok(0),
+        "rho" | "ρ" | "ohmxmm2/m" | "rs" | "sheet" => Ok(1),
+        "l" | "m" => Ok(2),
+        "a" | "mm2" | "d" | "mm" => Ok(3),
+        _ if resistivity::form(searched).is_some() => Ok(1),
+        _ => Err("searched variable not known"),
+    }
+}
+
+// one solved variable with its name and unit
+#[derive(Clone, Debug)]
+pub struct Solution {
+    pub name: &'static str,
+    pub value: f64,
+    pub unit: &'static str,
+}
+impl Solution {
+    // the value as it can be given again, like 0.0025A
+    pub fn given(&self) -> String {
+        format!("{}{}", self.value, self.unit)
+    }
+}
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.unit {
+            "V" | "A" | "Ω" | "m" => write!(f, "{} = {}", self.name, format_value(self.value, self.unit)),
+            "Ω/sq" => write!(f, "{} = {}/sq", self.name, format_value(self.value, "Ω")),
+            _ => write!(f, "{} = {} {}", self.name, self.value, self.unit),
+        }
+    }
+}
+
+// solves one variable. the given values are separated with a semicolon, the searched variable
+// can be given with its name or its unit
+pub fn solve(given: &str, searched: &str) -> Result<Solution, &'static str> {
+    let searched = match resistivity::form(searched.trim()) {
+        Some(form) => form.to_string(),
+        None => searched.trim().to_lowercase(),
+    };
+    let mut names: Vec<String> = Vec::new();
+    let mut strings: Vec<String> = Vec::new();
+    for i in given.split(';') {
+        if i.trim() == "" {
+            continue;
+        }
+        let (name, value) = split_given(i);
+        strings.push(elect_or_uri_value(&name, &value)?);
+        names.push(name);
+    }
+    if names.contains(&searched) {
+        return Err("the searched value is given too");
+    }
+    // U and I only exist in Ohm's law, a lonely R is decided by the searched value
+    let uri = names.iter().any(|name| name == "u" || name == "i")
+        || (names.iter().all(|name| name == "r")
+            && ["u", "v", "i", "a"].contains(&searched.as_str()));
+    let solution = |name: &'static str, value: Option<f64>, unit: &'static str| {
+        value.map(|value| Solution {
+            name,
+            value,
+            unit,
+        })
+    };
+    if uri {
+        let mut uri = URI::new(strings)?;
+        uri.calc(false);
+        let result = match searched.as_str() {
+            "u" | "v" => solution("U", uri.u, "V"),
+            "i" | "a" => solution("I", uri.i, "A"),
+            "r" | "ohm" | "Ω" => solution("R", uri.r, "Ω"),
+            _ => return Err("searched variable not known"),
+        };
+        match result {
+            Some(result) if names.len() == 2 => Ok(result),
+            _ => Err("give me two positive values of U, I and R"),
+        }
+    } else {
+        let index = elect_index(&searched)?;
+        let mut elect = Elect::new(strings)?;
+        if elect.calc(false) != index {
+            return Err("give me exactly three of R, rho, l and A or d");
+        }
+        if let Some(rho) = elect.rho {
+            if let Some(result) = rho_form(&elect, rho, &searched)? {
+                return Ok(result);
+            }
+        }
+        let area = elect.area.clone().unwrap_or_default();
+        let result = match searched.as_str() {
+            "r" | "ohm" | "Ω" => solution("R", elect.resistor, "Ω"),
+            "rho" | "ρ" | "ohmxmm2/m" => solution("ρ", elect.rho, "Ω·mm²/m"),
+            "l" | "m" => solution("l", elect.length, "m"),
+            "d" | "mm" => {
+                let mut area = Area { d: None, a: area.a };
+                area.calc_d();
+                solution("d", area.d, "mm")
+            }
+            _ => solution("A", area.a, "mm²"),
+        };
+        result.ok_or("the values give no result")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::strings;
+
+    #[test]
+    fn exit_codes() {
+        assert_eq!(run(Vec::new()), USAGE_ERROR);
+        assert_eq!(run(strings(&["nonsense"])), USAGE_ERROR);
+        assert_eq!(run(strings(&["--version"])), SUCCESS);
+        assert_eq!(run(strings(&["ohm", "u=12V", "r=6ohm"])), SUCCESS);
+        assert_eq!(run(strings(&["ohm", "u=12V"])), CALCULATION_ERROR);
+        assert_eq!(run(strings(&["help", "wire"])), SUCCESS);
+        assert_eq!(run(strings(&["help", "nonsense"])), USAGE_ERROR);
+        assert_eq!(run(strings(&["led", "--help"])), SUCCESS);
+        assert_eq!(run(strings(&["led", "u=12V"])), CALCULATION_ERROR);
+        assert_eq!(run(strings(&["tolerance", "nonsense", "12V"])), CALCULATION_ERROR);
+    }
+
+    #[test]
+    fn long_names() {
+        let args = ["--length", "10m", "--find=R", "--rho", "--d", "1mm", "copper"];
+        assert_eq!(
+            given_values(&strings(&args)),
+            strings(&["length=10m", "find=R", "rho", "d=1mm", "copper"])
+        );
+        assert_eq!(wire_name("diameter"), "d");
+        assert_eq!(wire_name("rho"), "rho");
+    }
+
+    #[test]
+    fn negative_values_are_usage_errors() {
+        assert_eq!(run(strings(&["ohm", "u=-12V", "r=6ohm"])), USAGE_ERROR);
+        assert_eq!(run(strings(&["ohm", "12V", "0ohm"])), USAGE_ERROR);
+        let args = ["wire", "copper", "--length", "-10m", "--area", "1.5mm2"];
+        assert_eq!(run(strings(&args)), USAGE_ERROR);
+        assert_eq!(solve("u=-12V; r=6ohm", "I").unwrap_err(), NOT_POSITIVE);
+    }
+
+    #[test]
+    fn solved_values() {
+        let current = solve("U=12V; R=4k7", "I").unwrap();
+        assert_eq!((current.name, current.unit), ("I", "A"));
+        assert!((current.value - 12f64 / 4700f64).abs() < 1e-12);
+        let resistor = solve("l=10m; d=1.5mm; copper", "R").unwrap();
+        assert!((resistor.value - 0.0974f64).abs() < 1e-4);
+        assert!(solve("U=12V; I=2A", "U").is_err());
+        assert!(solve("U=12V", "I").is_err());
+    }
+
+    #[test]
+    fn leftovers_and_forms_of_rho() {
+        // 8ohm of 1.72e-8ohmxm isn't taken as the resistance
+        assert_eq!(run(strings(&["wire", "1.72e-8ohmxm", "10m", "1.5mm2"])), CALCULATION_ERROR);
+        // the conductivity comes from the given material
+        let args = ["wire", "copper", "10m", "1.5mm2", "--find", "sigma"];
+        assert_eq!(run(strings(&args)), SUCCESS);
+        assert!(solve("copper; l=10m; a=1.5mm2; r=0.1ohm", "MS/m").is_err());
+        let sigma = solve("l=10m; a=1.5mm2; r=0.1147ohm", "MS/m").unwrap();
+        assert_eq!(sigma.name, "σ");
+        assert!((sigma.value - 58.1f64).abs() < 0.1f64);
+    }
+}
@@ -0,0 +1,283 @@
+// coils wound on a bobbin: the turns lie side by side over the winding length and the layers on
+// top of each other. with the inner diameter of the bobbin every layer is longer than the one
+// below, the wire length is the sum of all turns:
+//   l = sum of pi * (di + (2 k - 1) * do) * turns of the layer k    with the insulated wire do
+// the inductance comes from Wheeler's formulas for one layer or more, the resistance from Elect
+// with the bare wire. the coil is wound for a target inductance, a number of turns or until the
+// winding window is full
+use std::f64;
+
+use super::{format_value, parse_value, split_given, Elect};
+use convert::{awg_diameter, parse_gauge};
+use inductance::{multilayer, solenoid};
+
+// the most turns a coil is searched with for the target inductance
+const MAX_TURNS: u32 = 100000;
+
+// the density of the wire materials in g/cm³, for the mass
+fn density(material: &str) -> Option<f64> {
+    match material {
+        "copper" | "kupfer" => Some(8.96f64),
+        "aluminium" | "alu" => Some(2.70f64),
+        "silver" | "silber" => Some(10.49f64),
+        "gold" => Some(19.3f64),
+        "iron" | "eisen" => Some(7.87f64),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Coil {
+    bobbin: Option<f64>,   //m inner diameter of the winding
+    width: Option<f64>,    //m length of the winding
+    height: Option<f64>,   //m of the winding window
+    diameter: Option<f64>, //m of the bare wire
+    outer: Option<f64>,    //m of the wire with the insulation
+    target: Option<f64>,   //H
+    material: String,
+    temperature: Option<f64>, //°C
+    pub turns: Option<u32>,
+    pub layers: Option<u32>,
+    pub wire_length: Option<f64>, //m
+    pub resistance: Option<f64>,  //ohm
+    pub inductance: Option<f64>,  //H
+}
+impl Coil {
+    pub fn new(s: Vec<String>) -> Result<Coil, &'static str> {
+        let mut coil = Coil {
+            bobbin: None,
+            width: None,
+            height: None,
+            diameter: None,
+            outer: None,
+            target: None,
+            material: "copper".to_string(),
+            temperature: None,
+            turns: None,
+            layers: None,
+            wire_length: None,
+            resistance: None,
+            inductance: None,
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "di" | "bobbin" | "former" => coil.bobbin = Some(parse_value(&value, &["m"])?),
+                "b" | "width" | "winding" => coil.width = Some(parse_value(&value, &["m"])?),
+                "height" | "window" => coil.height = Some(parse_value(&value, &["m"])?),
+                // the wire like Elect as diameter or area, or as gauge
+                "d" | "wire" => coil.diameter = Some(parse_value(&value, &["m"])?),
+                "a" | "area" => {
+                    let area = parse_value(&value, &["mm2", "mm²"])?;
+                    coil.diameter = Some((area * 4f64 / f64::consts::PI).sqrt() / 1000f64);
+                }
+                "awg" | "gauge" => {
+                    coil.diameter = Some(awg_diameter(parse_gauge(value.trim_end_matches("AWG"))?))
+                }
+                "do" | "insulated" => coil.outer = Some(parse_value(&value, &["m"])?),
+                "lt" | "target" | "inductance" => coil.target = Some(parse_value(&value, &["H"])?),
+                "n" | "turns" => {
+                    coil.turns = match value.parse::<u32>() {
+                        Ok(n) if n > 0 => Some(n),
+                        _ => return Err("number of turns not parseable"),
+                    }
+                }
+                "t" | "temperature" => coil.temperature = Some(parse_value(&value, &["°C", "degC"])?),
+                "" | "material" | "mat" => coil.material = value.to_lowercase(),
+                _ => return Err("input is strange"),
+            }
+        }
+        let positive = |value: Option<f64>| value.is_some_and(|value| value > 0f64);
+        if !positive(coil.bobbin) || !positive(coil.width) {
+            return Err("give me the diameter di and the winding length b of the bobbin");
+        }
+        if !positive(coil.diameter) {
+            return Err("give me the wire as diameter d, area a or awg");
+        }
+        // the enamel of magnet wire adds about 8 % to the diameter
+        if coil.outer.is_none() {
+            coil.outer = coil.diameter.map(|diameter| diameter * 1.08f64);
+        }
+        if let (Some(outer), Some(diameter)) = (coil.outer, coil.diameter) {
+            if outer < diameter {
+                return Err("the insulated wire can't be thinner than the bare one");
+            }
+        }
+        match (coil.target, coil.turns, coil.height) {
+            (Some(_), Some(_), _) => return Err("give me either the inductance or the turns"),
+            (Some(target), None, _) if target <= 0f64 => {
+                return Err("the inductance has to be positive")
+            }
+            (None, None, None) => {
+                return Err("give me the inductance lt, the turns n or the height of the window")
+            }
+            _ => {}
+        }
+        if coil.height.is_some() && !positive(coil.height) {
+            return Err("the height of the window has to be positive");
+        }
+        // the material is checked by Elect
+        Elect::new(vec![coil.material.clone()])?;
+        Ok(coil)
+    }
+    // the turns of one layer
+    fn turns_per_layer(&self) -> u32 {
+        let width = self.width.unwrap_or(f64::NAN);
+        let outer = self.outer.unwrap_or(f64::NAN);
+        (width / outer).floor() as u32
+    }
+    // the layers, the wire length in m and the inductance in H of the coil with the turns
+    fn wind(&self, turns: u32) -> (u32, f64, f64) {
+        let bobbin = self.bobbin.unwrap_or(f64::NAN);
+        let outer = self.outer.unwrap_or(f64::NAN);
+        let per_layer = self.turns_per_layer();
+        let layers = turns.div_ceil(per_layer);
+        let mut wire_length = 0f64;
+        let mut left = turns;
+        for layer in 1..=layers {
+            let in_layer = left.min(per_layer);
+            wire_length += f64::consts::PI
+                * (bobbin + (2f64 * layer as f64 - 1f64) * outer)
+                * in_layer as f64;
+            left -= in_layer;
+        }
+        // the winding is as long as the turns of the first layer
+        let length = turns.min(per_layer) as f64 * outer;
+        let inductance = if layers == 1 {
+            solenoid(turns as f64, bobbin + outer, length)
+        } else {
+            let depth = layers as f64 * outer;
+            multilayer(turns as f64, bobbin + depth, length, depth)
+        };
+        (layers, wire_length, inductance)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        let per_layer = self.turns_per_layer();
+        if per_layer == 0 {
+            return Err("the wire is thicker than the winding length");
+        }
+        let outer = self.outer.unwrap_or(f64::NAN);
+        let turns = match (self.turns, self.target, self.height) {
+            (Some(turns), _, _) => turns,
+            // the inductance grows with the turns, so the first coil which reaches it is taken
+            (None, Some(target), _) => {
+                match (1..=MAX_TURNS).find(|turns| self.wind(*turns).2 >= target) {
+                    Some(turns) => turns,
+                    None => return Err("the inductance is too large for this bobbin and wire"),
+                }
+            }
+            // the window is filled with full layers
+            (None, None, Some(height)) => {
+                let layers = (height / outer).floor() as u32;
+                if layers == 0 {
+                    return Err("the wire is thicker than the winding window");
+                }
+                layers * per_layer
+            }
+            _ => return Err("give me the inductance lt, the turns n or the height of the window"),
+        };
+        let (layers, wire_length, inductance) = self.wind(turns);
+        // the resistance of the bare wire over the whole length
+        let diameter = self.diameter.unwrap_or(f64::NAN);
+        let area = f64::consts::PI * diameter * diameter / 4f64 * 1e6f64; //mm²
+        let mut values = vec![
+            self.material.clone(),
+            format!("{}m", wire_length),
+            format!("{}mm2", area),
+        ];
+        if let Some(temperature) = self.temperature {
+            values.push(format!("{}°C", temperature));
+        }
+        let mut elect = Elect::new(values)?;
+        elect.calc_resistor();
+        self.turns = Some(turns);
+        self.layers = Some(layers);
+        self.wire_length = Some(wire_length);
+        self.resistance = elect.resistor;
+        self.inductance = Some(inductance);
+        if should_print {
+            self.print_res(area)
+        }
+        Ok(())
+    }
+    fn print_res(&self, area: f64) {
+        let turns = self.turns.unwrap_or_default();
+        let layers = self.layers.unwrap_or_default();
+        let wire_length = self.wire_length.unwrap_or(0f64);
+        let resistance = self.resistance.unwrap_or(0f64);
+        let inductance = self.inductance.unwrap_or(0f64);
+        let outer = self.outer.unwrap_or(0f64);
+        println!(
+            "the coil has {} turns in {} layers of up to {} turns, the winding is {} high",
+            turns,
+            layers,
+            self.turns_per_layer(),
+            format_value(layers as f64 * outer, "m")
+        );
+        println!(
+            "the {} wire of {} ({:.4} mm²) is {} long",
+            self.material,
+            format_value(self.diameter.unwrap_or_default(), "m"),
+            area,
+            format_value(wire_length, "m")
+        );
+        println!(
+            "R = {} at {} °C, L = {}",
+            format_value(resistance, "Ω"),
+            self.temperature.unwrap_or(20f64),
+            format_value(inductance, "H")
+        );
+        // mm² * m are cm³
+        if let Some(density) = density(&self.material) {
+            println!("the wire has a mass of {}", format_value(area * wire_length * density, "g"));
+        }
+        if let Some(height) = self.height {
+            if layers as f64 * outer > height {
+                println!(
+                    "note: the winding doesn't fit into the window of {}",
+                    format_value(height, "m")
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::calculated;
+
+    fn coil(given: &[&str]) -> Result<Coil, &'static str> {
+        calculated(given, Coil::new, Coil::calc)
+    }
+
+    #[test]
+    fn one_layer() {
+        // 10 turns of 1 mm wire, insulated 1.08 mm, on a 10 mm bobbin
+        let coil = coil(&["di=10mm", "b=20mm", "d=1mm", "n=10"]).unwrap();
+        assert_eq!(coil.layers, Some(1));
+        let wire_length = f64::consts::PI * (10e-3f64 + 1.08e-3f64) * 10f64;
+        assert!((coil.wire_length.unwrap() - wire_length).abs() < 1e-12);
+        let inductance = solenoid(10f64, 11.08e-3f64, 10.8e-3f64);
+        assert!((coil.inductance.unwrap() - inductance).abs() < 1e-15);
+    }
+
+    #[test]
+    fn full_window() {
+        let coil = coil(&["di=10mm", "b=10mm", "d=0.5mm", "do=1mm", "height=3mm"]).unwrap();
+        assert_eq!(coil.turns, Some(30));
+        assert_eq!(coil.layers, Some(3));
+    }
+
+    #[test]
+    fn target_inductance() {
+        let coil = coil(&["di=10mm", "b=20mm", "d=0.5mm", "lt=10uH"]).unwrap();
+        assert!(coil.inductance.unwrap() >= 10e-6f64);
+    }
+
+    #[test]
+    fn strange_wire() {
+        assert!(coil(&["di=10mm", "b=20mm", "d=1mm", "do=0.9mm", "n=10"]).is_err());
+        assert!(coil(&["di=10mm", "b=0.5mm", "d=1mm", "n=10"]).is_err());
+    }
+}
@@ -0,0 +1,591 @@
+// resistor colour codes after IEC 60062 and the markings of smd resistors, in both directions.
+// the colours can be given in english or german like the materials in Elect
+use std::f64;
+
+use super::{parse_value, split_given, URI};
+use eseries::ESeries;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Colour {
+    Black,
+    Brown,
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Violet,
+    Grey,
+    White,
+    Gold,
+    Silver,
+    No,
+}
+impl Colour {
+    pub fn new(colour: &str) -> Result<Colour, &'static str> {
+        match colour.to_lowercase().as_str() {
+            "black" | "schwarz" => Ok(Colour::Black),
+            "brown" | "braun" => Ok(Colour::Brown),
+            "red" | "rot" => Ok(Colour::Red),
+            "orange" => Ok(Colour::Orange),
+            "yellow" | "gelb" => Ok(Colour::Yellow),
+            "green" | "grün" | "gruen" => Ok(Colour::Green),
+            "blue" | "blau" => Ok(Colour::Blue),
+            "violet" | "purple" | "violett" | "lila" => Ok(Colour::Violet),
+            "grey" | "gray" | "grau" => Ok(Colour::Grey),
+            "white" | "weiß" | "weiss" => Ok(Colour::White),
+            "gold" => Ok(Colour::Gold),
+            "silver" | "silber" => Ok(Colour::Silver),
+            "none" | "keine" => Ok(Colour::No),
+            _ => Err("colour not known"),
+        }
+    }
+    pub fn name(&self) -> (&'static str, &'static str) {
+        match self {
+            Colour::Black => ("black", "schwarz"),
+            Colour::Brown => ("brown", "braun"),
+            Colour::Red => ("red", "rot"),
+            Colour::Orange => ("orange", "orange"),
+            Colour::Yellow => ("yellow", "gelb"),
+            Colour::Green => ("green", "grün"),
+            Colour::Blue => ("blue", "blau"),
+            Colour::Violet => ("violet", "violett"),
+            Colour::Grey => ("grey", "grau"),
+            Colour::White => ("white", "weiß"),
+            Colour::Gold => ("gold", "gold"),
+            Colour::Silver => ("silver", "silber"),
+            Colour::No => ("none", "keine"),
+        }
+    }
+    fn from_digit(digit: u8) -> Colour {
+        match digit {
+            0 => Colour::Black,
+            1 => Colour::Brown,
+            2 => Colour::Red,
+            3 => Colour::Orange,
+            4 => Colour::Yellow,
+            5 => Colour::Green,
+            6 => Colour::Blue,
+            7 => Colour::Violet,
+            8 => Colour::Grey,
+            _ => Colour::White,
+        }
+    }
+    pub fn digit(&self) -> Result<u8, &'static str> {
+        match self {
+            Colour::Black => Ok(0),
+            Colour::Brown => Ok(1),
+            Colour::Red => Ok(2),
+            Colour::Orange => Ok(3),
+            Colour::Yellow => Ok(4),
+            Colour::Green => Ok(5),
+            Colour::Blue => Ok(6),
+            Colour::Violet => Ok(7),
+            Colour::Grey => Ok(8),
+            Colour::White => Ok(9),
+            _ => Err("gold, silver and none are no digits"),
+        }
+    }
+    // the power of ten of the multiplier band
+    pub fn multiplier(&self) -> Result<i32, &'static str> {
+        match self {
+            Colour::Gold => Ok(-1),
+            Colour::Silver => Ok(-2),
+            Colour::No => Err("none is no multiplier"),
+            _ => self.digit().map(|digit| digit as i32),
+        }
+    }
+    fn from_multiplier(pow: i32) -> Option<Colour> {
+        match pow {
+            -2 => Some(Colour::Silver),
+            -1 => Some(Colour::Gold),
+            0..=9 => Some(Colour::from_digit(pow as u8)),
+            _ => None,
+        }
+    }
+    // tolerance in %
+    pub fn tolerance(&self) -> Result<f64, &'static str> {
+        match self {
+            Colour::Brown => Ok(1f64),
+            Colour::Red => Ok(2f64),
+            Colour::Orange => Ok(0.05f64),
+            Colour::Yellow => Ok(0.02f64),
+            Colour::Green => Ok(0.5f64),
+            Colour::Blue => Ok(0.25f64),
+            Colour::Violet => Ok(0.1f64),
+            Colour::Grey => Ok(0.01f64),
+            Colour::Gold => Ok(5f64),
+            Colour::Silver => Ok(10f64),
+            Colour::No => Ok(20f64),
+            _ => Err("this colour is no tolerance"),
+        }
+    }
+    // temperature coefficient in ppm/K
+    fn temp_coefficient(&self) -> Result<f64, &'static str> {
+        match self {
+            Colour::Black => Ok(250f64),
+            Colour::Brown => Ok(100f64),
+            Colour::Red => Ok(50f64),
+            Colour::Orange => Ok(15f64),
+            Colour::Yellow => Ok(25f64),
+            Colour::Green => Ok(20f64),
+            Colour::Blue => Ok(10f64),
+            Colour::Violet => Ok(5f64),
+            Colour::Grey => Ok(1f64),
+            _ => Err("this colour is no temperature coefficient"),
+        }
+    }
+}
+
+static TOLERANCE_COLOURS: [Colour; 11] = [
+    Colour::Brown,
+    Colour::Red,
+    Colour::Orange,
+    Colour::Yellow,
+    Colour::Green,
+    Colour::Blue,
+    Colour::Violet,
+    Colour::Grey,
+    Colour::Gold,
+    Colour::Silver,
+    Colour::No,
+];
+static TEMP_COEFFICIENT_COLOURS: [Colour; 9] = [
+    Colour::Black,
+    Colour::Brown,
+    Colour::Red,
+    Colour::Orange,
+    Colour::Yellow,
+    Colour::Green,
+    Colour::Blue,
+    Colour::Violet,
+    Colour::Grey,
+];
+
+// splits the value in the significant digits and the power of ten, 4700 with 2 digits is (47, 2)
+fn significant_digits(value: f64, digits: i32) -> (u32, i32) {
+    let mut pow = value.log10().floor() as i32 - (digits - 1);
+    let mut significant = (value / 10f64.powi(pow)).round() as u32;
+    // rounding can give one digit more, 9.96 gives 100 instead of 99
+    if significant >= 10u32.pow(digits as u32) {
+        significant /= 10;
+        pow += 1;
+    }
+    (significant, pow)
+}
+
+// an EIA-96 marking like 01C, the number of the E96 value and the letter of the multiplier
+fn eia_96(smd: &str) -> Result<f64, &'static str> {
+    let code = smd[..2].parse::<usize>().unwrap_or(0);
+    if !(1..=96).contains(&code) {
+        return Err("EIA-96 code has to be between 01 and 96");
+    }
+    let pow = match smd[2..].chars().next() {
+        Some('Z') => -3,
+        Some('Y') | Some('R') => -2,
+        Some('X') | Some('S') => -1,
+        Some('A') => 0,
+        Some('B') | Some('H') => 1,
+        Some('C') => 2,
+        Some('D') => 3,
+        Some('E') => 4,
+        Some('F') => 5,
+        _ => return Err("EIA-96 multiplier letter not known"),
+    };
+    let significant = (ESeries::E96.values()[code - 1] * 100f64).round() as u32;
+    Ok(digits_value(significant, pow))
+}
+
+// the value from the digits and the power of ten without rounding noise
+pub fn digits_value(significant: u32, pow: i32) -> f64 {
+    if pow >= 0 {
+        significant as f64 * 10f64.powi(pow)
+    } else {
+        significant as f64 / 10f64.powi(-pow)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ColourCode {
+    bands: Vec<Colour>,
+    smd: Option<String>,
+    resistor: Option<f64>,         //ohm
+    tolerance: Option<f64>,        //%
+    temp_coefficient: Option<f64>, //ppm/K
+    band_count: usize,
+    // a marking like 47R read as EIA-96, where the R is the multiplier 0.01
+    eia_96: Option<f64>, //ohm
+    // the other given values go to URI together with the decoded resistor
+    uri_strings: Vec<String>,
+}
+impl ColourCode {
+    pub fn new(s: Vec<String>) -> Result<ColourCode, &'static str> {
+        let mut colour_code = ColourCode {
+            bands: Vec::new(),
+            smd: None,
+            resistor: None,
+            tolerance: None,
+            temp_coefficient: None,
+            band_count: 4,
+            eia_96: None,
+            uri_strings: Vec::new(),
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                // the colours can also be given in one string separated by spaces or dashes
+                "" | "colours" | "colors" | "farben" => {
+                    for colour in value.split([' ', '-', ',']) {
+                        if !colour.is_empty() {
+                            colour_code.bands.push(Colour::new(colour)?);
+                        }
+                    }
+                }
+                "smd" => colour_code.smd = Some(value.to_uppercase()),
+                "r" => colour_code.resistor = Some(parse_value(&value, &["ohm", "Ω"])?),
+                "tol" | "tolerance" => colour_code.tolerance = Some(parse_value(&value, &["%"])?),
+                "tc" | "tk" => {
+                    colour_code.temp_coefficient = Some(parse_value(&value, &["ppm/K"])?)
+                }
+                "bands" => {
+                    colour_code.band_count = match value.parse::<usize>() {
+                        Ok(n) if (3..=6).contains(&n) => n,
+                        _ => return Err("a resistor has 3 to 6 bands"),
+                    }
+                }
+                // URI only knows the base units, so the pre units are resolved here
+                "u" => colour_code.uri_strings.push(format!("{}V", parse_value(&value, &["V"])?)),
+                "i" => colour_code.uri_strings.push(format!("{}A", parse_value(&value, &["A"])?)),
+                _ => return Err("input is strange"),
+            }
+        }
+        let given = [
+            !colour_code.bands.is_empty(),
+            colour_code.smd.is_some(),
+            colour_code.resistor.is_some(),
+        ];
+        if given.iter().filter(|given| **given).count() != 1 {
+            return Err("give me either the colours, the smd marking or the resistance");
+        }
+        if let Some(resistor) = colour_code.resistor {
+            if !resistor.is_finite() || resistor < 0f64 {
+                return Err("the resistance can't be negative");
+            }
+        }
+        Ok(colour_code)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        if !self.bands.is_empty() {
+            self.decode_bands()?;
+        } else if self.smd.is_some() {
+            self.decode_smd()?;
+        }
+        if should_print {
+            self.print_res()?;
+        }
+        // the decoded resistor is the R input of URI
+        if !self.uri_strings.is_empty() {
+            if let Some(resistor) = self.resistor {
+                let mut uri_strings = self.uri_strings.clone();
+                uri_strings.push(format!("{}ohm", resistor));
+                let mut uri = URI::new(uri_strings)?;
+                uri.calc(should_print);
+            }
+        }
+        Ok(())
+    }
+    fn decode_bands(&mut self) -> Result<(), &'static str> {
+        let bands = self.bands.clone();
+        // the zero ohm resistor has a single black band
+        if bands == [Colour::Black] {
+            self.resistor = Some(0f64);
+            return Ok(());
+        }
+        // 3 bands: digit digit multiplier, 4 bands: + tolerance,
+        // 5 bands: three digits, multiplier and tolerance, 6 bands: + temperature coefficient
+        let digits = match bands.len() {
+            3 | 4 => 2,
+            5 | 6 => 3,
+            _ => return Err("a resistor has 3 to 6 bands"),
+        };
+        let mut significant = 0u32;
+        for band in bands[..digits].iter() {
+            significant = significant * 10 + band.digit()? as u32;
+        }
+        self.resistor = Some(digits_value(significant, bands[digits].multiplier()?));
+        self.tolerance = Some(if bands.len() > 3 {
+            bands[digits + 1].tolerance()?
+        } else {
+            20f64
+        });
+        if bands.len() == 6 {
+            self.temp_coefficient = Some(bands[5].temp_coefficient()?);
+        }
+        Ok(())
+    }
+    // 3 digits "472", 4 digits "4702", R as decimal point "4R7" and EIA-96 "01C"
+    fn decode_smd(&mut self) -> Result<(), &'static str> {
+        let smd = if let Some(smd) = self.smd.clone() {
+            smd
+        } else {
+            return Err("no smd marking");
+        };
+        let chars: Vec<char> = smd.chars().collect();
+        let eia_96_form = chars.len() == 3
+            && chars[0].is_ascii_digit()
+            && chars[1].is_ascii_digit()
+            && chars[2].is_ascii_alphabetic();
+        self.resistor = if smd == "0" {
+            // the zero ohm resistor, also as "000" below
+            Some(0f64)
+        } else if smd.contains('R') {
+            // the R is the decimal point, so 47R is 47 Ω. as EIA-96 the R would be the multiplier
+            // 0.01, that reading is shown too
+            if eia_96_form {
+                self.eia_96 = eia_96(&smd).ok();
+            }
+            match smd.replace("R", ".").parse::<f64>() {
+                Ok(resistor) => Some(resistor),
+                Err(_) => return Err("smd marking not parseable"),
+            }
+        } else if eia_96_form {
+            Some(eia_96(&smd)?)
+        } else if chars.iter().all(|c| c.is_ascii_digit()) && (chars.len() == 3 || chars.len() == 4)
+        {
+            let significant = smd[..chars.len() - 1].parse::<u32>().unwrap_or(0);
+            let pow = chars[chars.len() - 1].to_digit(10).unwrap_or(0) as i32;
+            Some(digits_value(significant, pow))
+        } else {
+            return Err("smd marking not known");
+        };
+        Ok(())
+    }
+    fn encode_bands(&self, resistor: f64) -> Result<Vec<Colour>, &'static str> {
+        if resistor == 0f64 {
+            return Ok(vec![Colour::Black]);
+        }
+        let digits = if self.band_count <= 4 { 2 } else { 3 };
+        let (significant, pow) = significant_digits(resistor, digits);
+        let mut bands: Vec<Colour> = significant
+            .to_string()
+            .chars()
+            .map(|c| Colour::from_digit(c.to_digit(10).unwrap_or(0) as u8))
+            .collect();
+        if let Some(multiplier) = Colour::from_multiplier(pow) {
+            bands.push(multiplier);
+        } else {
+            return Err("the resistance is out of the range of the colour code");
+        }
+        if self.band_count > 3 {
+            let tolerance = if let Some(tolerance) = self.tolerance {
+                tolerance
+            } else if self.band_count == 4 {
+                5f64
+            } else {
+                1f64
+            };
+            match TOLERANCE_COLOURS
+                .iter()
+                .find(|colour| colour.tolerance().ok() == Some(tolerance))
+            {
+                Some(colour) => bands.push(*colour),
+                None => return Err("there is no colour for this tolerance"),
+            }
+        }
+        if self.band_count == 6 {
+            let temp_coefficient = self.temp_coefficient.unwrap_or(100f64);
+            match TEMP_COEFFICIENT_COLOURS
+                .iter()
+                .find(|colour| colour.temp_coefficient().ok() == Some(temp_coefficient))
+            {
+                Some(colour) => bands.push(*colour),
+                None => return Err("there is no colour for this temperature coefficient"),
+            }
+        }
+        Ok(bands)
+    }
+    // all markings the resistance can have on a smd resistor
+    fn encode_smd(&self, resistor: f64) -> Vec<String> {
+        let mut markings: Vec<String> = Vec::new();
+        if resistor == 0f64 {
+            return vec!["0".to_string(), "000".to_string()];
+        }
+        if resistor < 10f64 {
+            // below 10 Ω the R is the decimal point
+            for digits in 2..4 {
+                let (significant, pow) = significant_digits(resistor, digits);
+                if digits_value(significant, pow) == resistor {
+                    let value = format!("{}", digits_value(significant, pow));
+                    let marking = if value.starts_with("0.") {
+                        value.replacen("0.", "R", 1)
+                    } else if value.contains('.') {
+                        value.replace(".", "R")
+                    } else {
+                        format!("{}R", value)
+                    };
+                    markings.push(marking);
+                    break;
+                }
+            }
+            return markings;
+        }
+        for digits in 2..4 {
+            let (significant, pow) = significant_digits(resistor, digits);
+            if digits_value(significant, pow) == resistor && (0..=9).contains(&pow) {
+                markings.push(format!("{}{}", significant, pow));
+            }
+        }
+        // EIA-96 only for the values of E96, the three digits of the code times the multiplier
+        let (significant, pow) = significant_digits(resistor, 3);
+        if digits_value(significant, pow) == resistor {
+            let position = ESeries::E96
+                .values()
+                .iter()
+                .position(|value| (value * 100f64).round() as u32 == significant);
+            let letter = match pow {
+                -3 => Some('Z'),
+                -2 => Some('Y'),
+                -1 => Some('X'),
+                0 => Some('A'),
+                1 => Some('B'),
+                2 => Some('C'),
+                3 => Some('D'),
+                4 => Some('E'),
+                5 => Some('F'),
+                _ => None,
+            };
+            if let (Some(position), Some(letter)) = (position, letter) {
+                markings.push(format!("{:02}{}", position + 1, letter));
+            }
+        }
+        markings
+    }
+    fn print_res(&self) -> Result<(), &'static str> {
+        let resistor = if let Some(resistor) = self.resistor {
+            resistor
+        } else {
+            return Err("no resistance");
+        };
+        let tolerance = if let Some(tolerance) = self.tolerance {
+            format!(" ± {} %", tolerance)
+        } else {
+            "".to_string()
+        };
+        let temp_coefficient = if let Some(tc) = self.temp_coefficient {
+            format!(" with {} ppm/K", tc)
+        } else {
+            "".to_string()
+        };
+        if !self.bands.is_empty() {
+            let names: Vec<&str> = self.bands.iter().map(|band| band.name().0).collect();
+            println!(
+                "The resistor with the colours {} is: {} Ω{}{}",
+                names.join(" "),
+                resistor,
+                tolerance,
+                temp_coefficient
+            );
+        } else if let Some(smd) = self.smd.clone() {
+            println!("The smd resistor with the marking {} is: {} Ω", smd, resistor);
+            if let Some(eia_96) = self.eia_96.filter(|eia_96| *eia_96 != resistor) {
+                println!("read as EIA-96 code the marking would be: {} Ω", eia_96);
+            }
+        } else {
+            let bands = self.encode_bands(resistor)?;
+            let english: Vec<&str> = bands.iter().map(|band| band.name().0).collect();
+            let german: Vec<&str> = bands.iter().map(|band| band.name().1).collect();
+            if resistor == 0f64 {
+                println!(
+                    "The zero ohm resistor has a single band: {} ({})",
+                    english.join(" "),
+                    german.join(" ")
+                );
+            } else {
+                println!(
+                    "The colour code for {} Ω{}{} with {} bands is: {} ({})",
+                    resistor,
+                    tolerance,
+                    temp_coefficient,
+                    self.band_count,
+                    english.join(" "),
+                    german.join(" ")
+                );
+                let digits = if self.band_count <= 4 { 2 } else { 3 };
+                let (significant, pow) = significant_digits(resistor, digits);
+                if digits_value(significant, pow) != resistor {
+                    println!("The resistance was rounded to {} Ω", digits_value(significant, pow));
+                }
+            }
+            let markings = self.encode_smd(resistor);
+            if !markings.is_empty() {
+                println!("The smd markings for {} Ω are: {}", resistor, markings.join(", "));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::strings;
+
+    fn colour_code(given: &[&str]) -> Result<ColourCode, &'static str> {
+        ColourCode::new(strings(given))
+    }
+
+    #[test]
+    fn significant_digits_round_up() {
+        assert_eq!(significant_digits(4700f64, 2), (47, 2));
+        assert_eq!(significant_digits(9.96f64, 2), (10, 0));
+        assert_eq!(digits_value(47, -1), 4.7f64);
+    }
+
+    #[test]
+    fn decode_four_bands() {
+        let mut code = colour_code(&["yellow violet red gold"]).unwrap();
+        code.calc(false).unwrap();
+        assert_eq!(code.resistor, Some(4700f64));
+        assert_eq!(code.tolerance, Some(5f64));
+    }
+
+    #[test]
+    fn zero_ohm() {
+        let code = colour_code(&["r=0"]).unwrap();
+        assert_eq!(code.encode_bands(0f64), Ok(vec![Colour::Black]));
+        assert_eq!(code.encode_smd(0f64), vec!["0", "000"]);
+        let mut code = colour_code(&["black"]).unwrap();
+        code.calc(false).unwrap();
+        assert_eq!(code.resistor, Some(0f64));
+        assert!(colour_code(&["r=-1ohm"]).is_err());
+    }
+
+    #[test]
+    fn eia_96() {
+        let smd = |marking: &str| {
+            let mut code = colour_code(&[&format!("smd={}", marking)]).unwrap();
+            code.calc(false).map(|_| code.resistor)
+        };
+        assert_eq!(smd("01C"), Ok(Some(10e3f64)));
+        assert_eq!(smd("68X"), Ok(Some(49.9f64)));
+        assert_eq!(smd("4R7"), Ok(Some(4.7f64)));
+        // two digits and R are read with the R as decimal point, EIA-96 is the other reading
+        assert_eq!(smd("10R"), Ok(Some(10f64)));
+        assert_eq!(smd("47R"), Ok(Some(47f64)));
+        let mut code = colour_code(&["smd=47R"]).unwrap();
+        code.calc(false).unwrap();
+        assert_eq!(code.eia_96, Some(3.01f64));
+        assert_eq!(smd("01R"), Ok(Some(1f64)));
+        assert_eq!(smd("472"), Ok(Some(4700f64)));
+        let code = colour_code(&["r=10kohm"]).unwrap();
+        assert!(code.encode_smd(10e3f64).contains(&"01C".to_string()));
+    }
+
+    #[test]
+    fn uri_with_pre_units() {
+        let code = colour_code(&["r=4k7", "i=20mA"]).unwrap();
+        assert_eq!(code.uri_strings, vec!["0.02A"]);
+        let mut code = colour_code(&["r=4k7", "i=20mA"]).unwrap();
+        assert!(code.calc(false).is_ok());
+    }
+}
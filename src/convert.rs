@@ -0,0 +1,231 @@
+// conversion of a value to another unit, like convert 2.5mm2 to kcmil or convert 3 kWh to MJ.
+// the units which are only a factor come from the quantity module, so every unit the expressions
+// know can be converted. AWG and the levels like dBm or dBµV aren't a factor, the gauge is
+// calculated here and the levels in decibel. a power and a voltage are converted into each other at
+// an impedance, like convert 0dBm to dBµV at 75ohm (50 Ω without)
+use std::f64;
+
+use super::parse_value;
+use decibel;
+use expression;
+use quantity::{Dimension, Quantity};
+
+const LENGTH: Dimension = Dimension([1, 0, 0, 0, 0]);
+const AREA: Dimension = Dimension([2, 0, 0, 0, 0]);
+
+// the units which aren't only a factor
+fn special_units() -> Vec<&'static str> {
+    let mut units = vec!["AWG", "gauge"];
+    units.extend(decibel::level_names());
+    units
+}
+
+// the diameter of the american wire gauge in m, 36 AWG is 0.127 mm and 39 gauges further the
+// diameter is 92 times larger
+pub fn awg_diameter(gauge: f64) -> f64 {
+    0.127e-3f64 * 92f64.powf((36f64 - gauge) / 39f64)
+}
+fn awg_gauge(diameter: f64) -> f64 {
+    36f64 - 39f64 * (diameter / 0.127e-3f64).ln() / 92f64.ln()
+}
+
+// the thick gauges are 0, 00 (or 2/0), 000 and 0000 (4/0), they count on below zero
+pub fn parse_gauge(number: &str) -> Result<f64, &'static str> {
+    if let Some(zeros) = number.strip_suffix("/0") {
+        if let Ok(zeros) = zeros.parse::<u8>() {
+            return Ok(1f64 - zeros as f64);
+        }
+    }
+    if number.len() > 1 && number.chars().all(|c| c == '0') {
+        return Ok(1f64 - number.len() as f64);
+    }
+    match number.parse::<f64>() {
+        Ok(gauge) => Ok(gauge),
+        Err(_) => Err("gauge not parseable"),
+    }
+}
+
+// the number and the special unit, like 20 and AWG for "20 AWG"
+fn split_special(input: &str) -> Option<(&str, &str)> {
+    let input = input.trim();
+    for unit in special_units() {
+        if let Some(number) = input.strip_suffix(unit) {
+            if number.trim() != "" {
+                return Some((number.trim(), unit));
+            }
+        }
+    }
+    None
+}
+
+// a value with a special unit as quantity: the gauge as diameter, a level as power or voltage
+fn from_special(number: &str, unit: &str) -> Result<Quantity, &'static str> {
+    if unit == "AWG" || unit == "gauge" {
+        return Ok(Quantity::new(awg_diameter(parse_gauge(number)?), LENGTH));
+    }
+    let level = expression::evaluate(number, &|_| None)?;
+    if !level.is_dimensionless() {
+        return Err("a level in dB has no unit");
+    }
+    decibel::from_level(level.value, unit)
+}
+
+// the quantity in the special unit. AWG takes a diameter or the area of a round wire
+fn to_special(value: &Quantity, unit: &str, impedance: f64) -> Result<f64, &'static str> {
+    if unit == "AWG" || unit == "gauge" {
+        let diameter = if value.dimension == LENGTH {
+            value.value
+        } else if value.dimension == AREA {
+            (4f64 * value.value / f64::consts::PI).sqrt()
+        } else {
+            return Err("only a diameter or an area can be given in AWG");
+        };
+        if diameter <= 0f64 {
+            return Err("the diameter has to be positive");
+        }
+        return Ok(awg_gauge(diameter));
+    }
+    decibel::to_level(value, unit, impedance)
+}
+
+// six significant digits, very large or small numbers with exponent
+pub fn significant(value: f64) -> String {
+    if value == 0f64 {
+        return "0".to_string();
+    }
+    if value.abs() >= 1e9f64 || value.abs() < 1e-4f64 {
+        return format!("{:.5e}", value);
+    }
+    let digits = (5 - value.abs().log10().floor() as i32).max(0) as usize;
+    let number = format!("{:.*}", digits, value);
+    if number.contains('.') {
+        number.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        number
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Conversion {
+    input: String,
+    target: String,
+    impedance: f64, //ohm, for levels
+    pub result: Option<f64>,
+}
+impl Conversion {
+    // the values are one text like "2.5mm2 to kcmil", the last to splits value and unit. at gives
+    // the impedance for levels
+    pub fn new(s: Vec<String>) -> Result<Conversion, &'static str> {
+        let mut text = s.join(" ");
+        let mut impedance = 50f64;
+        if let Some(split_point) = text.rfind(" at ") {
+            impedance = parse_value(&text[split_point + 4..], &["ohm", "Ω"])?;
+            if impedance <= 0f64 {
+                return Err("the impedance has to be positive");
+            }
+            text.truncate(split_point);
+        }
+        let split_point = match text.rfind(" to ") {
+            Some(split_point) => split_point,
+            None => return Err("give me the value and the unit like 2.5mm2 to kcmil"),
+        };
+        let input = text[..split_point].trim().to_string();
+        let target = text[split_point + 4..].trim().to_string();
+        if input.is_empty() || target.is_empty() {
+            return Err("give me the value and the unit like 2.5mm2 to kcmil");
+        }
+        Ok(Conversion {
+            input,
+            target,
+            impedance,
+            result: None,
+        })
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        let value = match split_special(&self.input) {
+            Some((number, unit)) => from_special(number, unit)?,
+            None => expression::evaluate(&self.input, &|_| None)?,
+        };
+        let result = if special_units().contains(&self.target.as_str()) {
+            to_special(&value, &self.target, self.impedance)?
+        } else {
+            let unit = match expression::evaluate(&self.target, &|_| None) {
+                Ok(unit) => unit,
+                Err(_) => return Err("unit to convert to not known"),
+            };
+            // a gauge is a diameter, but it can be asked for as area of the wire too
+            let is_gauge = matches!(
+                split_special(&self.input),
+                Some((_, "AWG")) | Some((_, "gauge"))
+            );
+            let is_level = value.dimension == decibel::POWER || value.dimension == decibel::VOLTAGE;
+            if is_gauge && unit.dimension == AREA {
+                (f64::consts::PI / 4f64 * value.value * value.value) / unit.value
+            } else if is_level && split_special(&self.input).is_some() {
+                // a level like dBµV can be given as power too
+                decibel::at_impedance(&value, unit.dimension, self.impedance)?.in_unit(&unit)?
+            } else if value.dimension != unit.dimension {
+                return Err("the units don't fit together, they are different quantities");
+            } else {
+                value.in_unit(&unit)?
+            }
+        };
+        self.result = Some(result);
+        if should_print {
+            self.print_res()
+        }
+        Ok(())
+    }
+    fn print_res(&self) {
+        if let Some(result) = self.result {
+            // 1 is a number without unit
+            let unit = match self.target.as_str() {
+                "1" => "".to_string(),
+                target if target.starts_with('%') => target.to_string(),
+                target => format!(" {}", target),
+            };
+            println!("{} = {}{}", self.input, significant(result), unit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(text: &str) -> Result<f64, &'static str> {
+        let mut conversion = Conversion::new(vec![text.to_string()])?;
+        conversion.calc(false)?;
+        Ok(conversion.result.unwrap_or(f64::NAN))
+    }
+
+    #[test]
+    fn american_wire_gauge() {
+        assert!((awg_diameter(36f64) - 0.127e-3f64).abs() < 1e-15);
+        assert!((awg_diameter(10f64) - 2.588e-3f64).abs() < 1e-6);
+        assert!((awg_gauge(awg_diameter(24f64)) - 24f64).abs() < 1e-9);
+        assert_eq!(parse_gauge("4/0"), Ok(-3f64));
+        assert_eq!(parse_gauge("00"), Ok(-1f64));
+        assert_eq!(parse_gauge("0"), Ok(0f64));
+        assert!(parse_gauge("x").is_err());
+    }
+
+    #[test]
+    fn conversions() {
+        assert!((convert("2.5mm2 to kcmil").unwrap() - 4.934f64).abs() < 1e-3);
+        assert!((convert("3 kWh to MJ").unwrap() - 10.8f64).abs() < 1e-9);
+        assert!((convert("10 AWG to mm2").unwrap() - 5.261f64).abs() < 1e-3);
+        assert!((convert("0dBm to dBµV at 75ohm").unwrap() - 108.751f64).abs() < 1e-3);
+        assert!((convert("1V to dBm").unwrap() - 13.0103f64).abs() < 1e-4);
+        assert!(convert("1m to V").is_err());
+        assert!(convert("1m").is_err());
+    }
+
+    #[test]
+    fn six_significant_digits() {
+        assert_eq!(significant(0f64), "0");
+        assert_eq!(significant(1.5f64), "1.5");
+        assert_eq!(significant(1234567.8f64), "1234568");
+        assert_eq!(significant(0.000012345f64), "1.23450e-5");
+    }
+}
@@ -0,0 +1,338 @@
+// decibels: gains and losses as power or voltage ratio, the absolute levels dBm, dBW, dBV and dBµV
+// and chains of gains and losses like amplifiers, attenuators and cables. a power and a voltage
+// level are converted into each other at a reference impedance (50 Ω if none is given):
+//   db ratio=2                              3.01 dB as power ratio, 6.02 dB as voltage ratio
+//   db 10dBm z=75ohm                        the level in W, V, dBm, dBW, dBV and dBµV
+//   db 0dBm -3dB +20dB a=6.6dB/100m l=25m   the level after every stage of the chain
+use std::f64;
+
+use super::{format_value, parse_value, split_given};
+use expression;
+use quantity::{Dimension, Quantity};
+
+pub const POWER: Dimension = Dimension([2, 1, -3, 0, 0]);
+pub const VOLTAGE: Dimension = Dimension([2, 1, -3, -1, 0]);
+
+// the absolute levels with their reference, a power in W or a voltage in V. the longer names come
+// first, so dBm doesn't take the m of dBmV
+static LEVELS: [(&str, f64, bool); 7] = [
+    ("dBµV", 1e-6f64, false),
+    ("dBuV", 1e-6f64, false),
+    ("dBmV", 1e-3f64, false),
+    ("dBm", 1e-3f64, true),
+    ("dBW", 1f64, true),
+    ("dBk", 1e3f64, true),
+    ("dBV", 1f64, false),
+];
+
+// the names of the levels, for the conversions
+pub fn level_names() -> Vec<&'static str> {
+    LEVELS.iter().map(|level| level.0).collect()
+}
+
+pub fn power_db(ratio: f64) -> f64 {
+    10f64 * ratio.log10()
+}
+pub fn voltage_db(ratio: f64) -> f64 {
+    20f64 * ratio.log10()
+}
+
+// the reference of the level as quantity, 1 mW for dBm
+fn reference(unit: &str) -> Option<Quantity> {
+    LEVELS.iter().find(|level| level.0 == unit).map(|level| {
+        Quantity::new(level.1, if level.2 { POWER } else { VOLTAGE })
+    })
+}
+
+// a power as voltage at the impedance or the other way round, so dBm and dBµV fit together
+pub fn at_impedance(
+    value: &Quantity,
+    dimension: Dimension,
+    impedance: f64,
+) -> Result<Quantity, &'static str> {
+    if value.dimension == dimension {
+        Ok(*value)
+    } else if value.dimension == POWER && dimension == VOLTAGE {
+        Ok(Quantity::new((value.value * impedance).sqrt(), VOLTAGE))
+    } else if value.dimension == VOLTAGE && dimension == POWER {
+        Ok(Quantity::new(value.value * value.value / impedance, POWER))
+    } else {
+        Err("only a power or a voltage has a level in dB")
+    }
+}
+
+// the power or voltage of the level, like 10 mW for 10 dBm
+pub fn from_level(level: f64, unit: &str) -> Result<Quantity, &'static str> {
+    let reference = match reference(unit) {
+        Some(reference) => reference,
+        None => return Err("level not known"),
+    };
+    let ratio = if reference.dimension == POWER {
+        10f64.powf(level / 10f64)
+    } else {
+        10f64.powf(level / 20f64)
+    };
+    Ok(Quantity::new(reference.value * ratio, reference.dimension))
+}
+
+// the level of a power or a voltage, the other one is taken at the impedance
+pub fn to_level(value: &Quantity, unit: &str, impedance: f64) -> Result<f64, &'static str> {
+    let reference = match reference(unit) {
+        Some(reference) => reference,
+        None => return Err("level not known"),
+    };
+    let value = at_impedance(value, reference.dimension, impedance)?;
+    if value.value <= 0f64 {
+        return Err("only a positive power or voltage has a level in dB");
+    }
+    if reference.dimension == POWER {
+        Ok(power_db(value.value / reference.value))
+    } else {
+        Ok(voltage_db(value.value / reference.value))
+    }
+}
+
+// a value like 10dBm or -3dB, the number and the unit. the number can be an expression
+pub fn split_level(input: &str) -> Option<(f64, &'static str)> {
+    let input = input.trim();
+    for unit in level_names().into_iter().chain(Some("dB")) {
+        if let Some(number) = input.strip_suffix(unit) {
+            if let Ok(number) = expression::evaluate(number.trim_start_matches('+'), &|_| None) {
+                if number.is_dimensionless() {
+                    return Some((number.value, unit));
+                }
+            }
+        }
+    }
+    None
+}
+
+// one gain or loss of the chain
+#[derive(Clone, Debug)]
+struct Stage {
+    name: String,
+    gain: f64, //dB, negative for a loss
+}
+
+#[derive(Clone, Debug)]
+pub struct Decibel {
+    ratio: Option<f64>,
+    input: Option<Quantity>, // the level at the start of the chain as power or voltage
+    stages: Vec<Stage>,
+    attenuation: Option<f64>, //dB/m of a cable
+    length: Option<f64>,      //m of the cable
+    impedance: f64,           //ohm
+    pub output: Option<Quantity>,
+}
+impl Decibel {
+    pub fn new(s: Vec<String>) -> Result<Decibel, &'static str> {
+        let mut decibel = Decibel {
+            ratio: None,
+            input: None,
+            stages: Vec::new(),
+            attenuation: None,
+            length: None,
+            impedance: 50f64,
+            output: None,
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "ratio" => {
+                    decibel.ratio = match expression::evaluate(&value, &|_| None) {
+                        Ok(ratio) if ratio.is_dimensionless() && ratio.value > 0f64 => {
+                            Some(ratio.value)
+                        }
+                        _ => return Err("the ratio has to be a positive number"),
+                    }
+                }
+                "z" | "impedance" => {
+                    decibel.impedance = parse_value(&value, &["ohm", "Ω"])?;
+                    if decibel.impedance <= 0f64 {
+                        return Err("the impedance has to be positive");
+                    }
+                }
+                // the attenuation of a cable like 6.6dB/100m
+                "a" | "attenuation" => {
+                    let split_point = match value.find("dB/") {
+                        Some(split_point) => split_point,
+                        None => return Err("give me the attenuation like 6.6dB/100m"),
+                    };
+                    let loss = match expression::evaluate(&value[..split_point], &|_| None) {
+                        Ok(loss) if loss.is_dimensionless() => loss.value,
+                        _ => return Err("attenuation not parseable"),
+                    };
+                    let per = &value[split_point + 3..];
+                    // dB/m has no number in front of the unit
+                    let per = if per.starts_with(|c: char| c.is_ascii_digit()) {
+                        per.to_string()
+                    } else {
+                        format!("1{}", per)
+                    };
+                    let per = parse_value(&per, &["m"])?;
+                    if per <= 0f64 {
+                        return Err("the length of the attenuation has to be positive");
+                    }
+                    decibel.attenuation = Some(loss.abs() / per);
+                }
+                "l" | "length" => decibel.length = Some(parse_value(&value, &["m"])?),
+                "" | "gain" | "loss" | "level" => match split_level(&value) {
+                    Some((gain, "dB")) => decibel.stages.push(Stage {
+                        name: if name.is_empty() { "gain".to_string() } else { name.clone() },
+                        // a loss is a loss, also if it is given as positive number
+                        gain: if name == "loss" { -gain.abs() } else { gain },
+                    }),
+                    Some((level, unit)) => {
+                        if decibel.input.is_some() {
+                            return Err("a chain has only one level at the start");
+                        }
+                        decibel.input = Some(from_level(level, unit)?);
+                    }
+                    None => {
+                        // a power or voltage like 1mW or 2V as level at the start
+                        let value = expression::evaluate(&value, &|_| None)?;
+                        if value.dimension != POWER && value.dimension != VOLTAGE {
+                            return Err("give me a gain in dB or a level like 10dBm, 1mW or 2V");
+                        }
+                        if decibel.input.is_some() {
+                            return Err("a chain has only one level at the start");
+                        }
+                        decibel.input = Some(value);
+                    }
+                },
+                _ => return Err("quantity not known"),
+            }
+        }
+        match (decibel.attenuation, decibel.length) {
+            (Some(attenuation), Some(length)) => decibel.stages.push(Stage {
+                name: format!("cable {}", format_value(length, "m")),
+                gain: -attenuation * length,
+            }),
+            (None, None) => {}
+            _ => return Err("the cable needs the attenuation and the length"),
+        }
+        if decibel.ratio.is_none() && decibel.input.is_none() && decibel.stages.is_empty() {
+            return Err("give me a ratio, a level or gains in dB");
+        }
+        Ok(decibel)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        if let Some(input) = self.input {
+            let gain: f64 = self.stages.iter().map(|stage| stage.gain).sum();
+            let power = at_impedance(&input, POWER, self.impedance)?;
+            self.output = Some(Quantity::new(power.value * 10f64.powf(gain / 10f64), POWER));
+        }
+        if should_print {
+            self.print_res()?
+        }
+        Ok(())
+    }
+    // the level in dBm and dBµV, the other ones are printed for a single level
+    fn level(&self, power: &Quantity) -> Result<String, &'static str> {
+        Ok(format!(
+            "{:.2} dBm, {:.2} dBµV",
+            to_level(power, "dBm", self.impedance)?,
+            to_level(power, "dBµV", self.impedance)?
+        ))
+    }
+    fn print_res(&self) -> Result<(), &'static str> {
+        if let Some(ratio) = self.ratio {
+            println!("a power ratio of {} are {:.3} dB", ratio, power_db(ratio));
+            println!("a voltage ratio of {} are {:.3} dB", ratio, voltage_db(ratio));
+        }
+        let gain: f64 = self.stages.iter().map(|stage| stage.gain).sum();
+        match self.input {
+            Some(input) => {
+                let power = at_impedance(&input, POWER, self.impedance)?;
+                if self.stages.is_empty() {
+                    let voltage = at_impedance(&input, VOLTAGE, self.impedance)?;
+                    println!(
+                        "the level at {} is {} or {}",
+                        format_value(self.impedance, "Ω"),
+                        format_value(power.value, "W"),
+                        format_value(voltage.value, "V")
+                    );
+                    for level in LEVELS.iter().filter(|level| level.0 != "dBuV") {
+                        println!("  {:>7.2} {}", to_level(&power, level.0, self.impedance)?, level.0);
+                    }
+                    return Ok(());
+                }
+                println!("{:<16} {:>9} {}", "input", "", self.level(&power)?);
+                let mut level = power.value;
+                for stage in self.stages.iter() {
+                    level *= 10f64.powf(stage.gain / 10f64);
+                    println!(
+                        "{:<16} {:>+6.2} dB {}",
+                        stage.name,
+                        stage.gain,
+                        self.level(&Quantity::new(level, POWER))?
+                    );
+                }
+                if let Some(output) = self.output {
+                    let voltage = at_impedance(&output, VOLTAGE, self.impedance)?;
+                    println!(
+                        "the output is {} or {} at {} after a gain of {:.2} dB",
+                        format_value(output.value, "W"),
+                        format_value(voltage.value, "V"),
+                        format_value(self.impedance, "Ω"),
+                        gain
+                    );
+                }
+            }
+            None if !self.stages.is_empty() => {
+                for stage in self.stages.iter() {
+                    println!("{:<16} {:>+6.2} dB", stage.name, stage.gain);
+                }
+                println!(
+                    "the gain of {:.2} dB is a power ratio of {:.4} and a voltage ratio of {:.4}",
+                    gain,
+                    10f64.powf(gain / 10f64),
+                    10f64.powf(gain / 20f64)
+                );
+            }
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::calculated;
+
+    fn decibel(given: &[&str]) -> Result<Decibel, &'static str> {
+        calculated(given, Decibel::new, Decibel::calc)
+    }
+
+    #[test]
+    fn ratios() {
+        assert!((power_db(2f64) - 3.0103f64).abs() < 1e-4);
+        assert!((voltage_db(2f64) - 6.0206f64).abs() < 1e-4);
+        assert_eq!(split_level("10dBm"), Some((10f64, "dBm")));
+        assert_eq!(split_level("+20dB"), Some((20f64, "dB")));
+        assert_eq!(split_level("3dBmV"), Some((3f64, "dBmV")));
+        assert_eq!(split_level("3V"), None);
+    }
+
+    #[test]
+    fn levels() {
+        let power = from_level(10f64, "dBm").unwrap();
+        assert!((power.value - 10e-3f64).abs() < 1e-15);
+        assert!((to_level(&power, "dBW", 50f64).unwrap() + 20f64).abs() < 1e-9);
+        // 0 dBm at 50 Ω are 223.6 mV or 107 dBµV
+        let voltage = at_impedance(&from_level(0f64, "dBm").unwrap(), VOLTAGE, 50f64).unwrap();
+        assert!((voltage.value - 0.22361f64).abs() < 1e-5);
+        assert!((to_level(&voltage, "dBµV", 50f64).unwrap() - 106.99f64).abs() < 1e-2);
+    }
+
+    #[test]
+    fn chain_with_cable() {
+        let chain = decibel(&["0dBm", "+20dB", "loss=3dB", "a=6.6dB/100m", "l=50m"]).unwrap();
+        let output = to_level(&chain.output.unwrap(), "dBm", 50f64).unwrap();
+        assert!((output - 13.7f64).abs() < 1e-9);
+        assert!(decibel(&["a=6.6dB/100m"]).is_err());
+        assert!(decibel(&["0dBm", "3dBm"]).is_err());
+        assert!(decibel(&["1m"]).is_err());
+    }
+}
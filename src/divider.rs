@@ -0,0 +1,170 @@
+// design of a voltage divider with a load. the divider current is chosen as a multiple of the load
+// current, so the output voltage doesn't break down too much when the load changes
+use std::f64;
+
+use super::{parse_value, split_given, Current, Voltage, URI};
+use eseries::ESeries;
+
+// Ohm's law with URI, two of U, I and R are given as strings
+fn ohms_law(given: Vec<String>) -> Result<URI, &'static str> {
+    let mut uri = URI::new(given)?;
+    uri.calc(false);
+    Ok(uri)
+}
+
+#[derive(Clone, Debug)]
+pub struct Divider {
+    u_in: Option<f64>,    //V
+    u_out: Option<f64>,   //V wanted without the rounding to the e-series
+    r_load: Option<f64>,  //ohm
+    i_load: Option<f64>,  //A
+    i_cross: Option<f64>, //A through R2
+    factor: f64,          //I_cross / I_load
+    series: Option<ESeries>,
+    r1: Option<f64>, //ohm
+    r2: Option<f64>, //ohm
+}
+impl Divider {
+    pub fn new(s: Vec<String>) -> Result<Divider, &'static str> {
+        let mut divider = Divider {
+            u_in: None,
+            u_out: None,
+            r_load: None,
+            i_load: None,
+            i_cross: None,
+            factor: 10f64,
+            series: None,
+            r1: None,
+            r2: None,
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "uin" | "ue" => divider.u_in = Some(Voltage::new(value)?.base_value()),
+                "uout" | "ua" => divider.u_out = Some(Voltage::new(value)?.base_value()),
+                "rl" => divider.r_load = Some(parse_value(&value, &["ohm", "Ω"])?),
+                "il" => divider.i_load = Some(Current::new(value)?.base_value()),
+                "iq" => divider.i_cross = Some(Current::new(value)?.base_value()),
+                "k" => divider.factor = parse_value(&value, &[])?,
+                "series" | "" => divider.series = Some(ESeries::new(&value)?),
+                _ => return Err("input is strange"),
+            }
+        }
+        match (divider.u_in, divider.u_out) {
+            (Some(u_in), Some(u_out)) if u_out > 0f64 && u_out < u_in => {}
+            (Some(_), Some(_)) => return Err("the output voltage has to be between 0 and Uin"),
+            _ => return Err("give me Uin and Uout"),
+        }
+        if divider.r_load.is_some() && divider.i_load.is_some() {
+            return Err("give me only one of the load resistance and the load current");
+        }
+        if divider.i_cross.is_none() && divider.r_load.is_none() && divider.i_load.is_none() {
+            return Err("give me the load or the current through the divider");
+        }
+        Ok(divider)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        let u_in = self.u_in.unwrap_or(f64::NAN);
+        let u_out = self.u_out.unwrap_or(f64::NAN);
+        // the load current from the load resistance with Ohm's law
+        let i_load = if let Some(r_load) = self.r_load {
+            let uri = ohms_law(vec![format!("{}V", u_out), format!("{}ohm", r_load)])?;
+            if let Some(i) = uri.i { i } else { return Err("load not usable") }
+        } else {
+            self.i_load.unwrap_or(0f64)
+        };
+        let i_cross = if let Some(i_cross) = self.i_cross {
+            i_cross
+        } else {
+            self.factor * i_load
+        };
+        self.i_cross = Some(i_cross);
+        // R2 = Uout / Iq and R1 = (Uin - Uout) / (Iq + IL)
+        let uri = ohms_law(vec![format!("{}V", u_out), format!("{}A", i_cross)])?;
+        let r2 = if let Some(r) = uri.r { r } else { return Err("R2 not calculable") };
+        let uri = ohms_law(vec![
+            format!("{}V", u_in - u_out),
+            format!("{}A", i_cross + i_load),
+        ])?;
+        let r1 = if let Some(r) = uri.r { r } else { return Err("R1 not calculable") };
+        self.r1 = Some(r1);
+        self.r2 = Some(r2);
+        if should_print {
+            println!(
+                "The divider for {} V to {} V with {} A through R2 is: R1 = {} Ω and R2 = {} Ω",
+                u_in, u_out, i_cross, r1, r2
+            );
+            self.print_res(r1, r2, i_load);
+            if let Some(series) = self.series {
+                let (r1, r2) = (series.nearest(r1), series.nearest(r2));
+                println!(
+                    "Snapped to {:?} the divider is: R1 = {} Ω and R2 = {} Ω",
+                    series, r1, r2
+                );
+                self.print_res(r1, r2, i_load);
+            }
+        }
+        Ok(())
+    }
+    // the real divider with the resistors r1 and r2, the source is taken as ideal
+    fn print_res(&self, r1: f64, r2: f64, i_load: f64) {
+        let u_in = self.u_in.unwrap_or(0f64);
+        let u_out = self.u_out.unwrap_or(0f64);
+        // the thevenin equivalent of the divider
+        let u_open = u_in * r2 / (r1 + r2);
+        let r_out = r1 * r2 / (r1 + r2);
+        let u_loaded = if let Some(r_load) = self.r_load {
+            u_open * r_load / (r_out + r_load)
+        } else {
+            u_open - i_load * r_out
+        };
+        let i1 = (u_in - u_loaded) / r1;
+        let i2 = u_loaded / r2;
+        println!("the output voltage without load is: {} V", u_open);
+        println!(
+            "the output voltage with load is: {} V ({:+.3} %)",
+            u_loaded,
+            (u_loaded - u_out) / u_out * 100f64
+        );
+        println!("the output impedance is: {} Ω", r_out);
+        println!("the current through R1 is: {} A and through R2: {} A", i1, i2);
+        println!(
+            "the power in R1 is: {} W and in R2: {} W",
+            i1 * i1 * r1,
+            i2 * i2 * r2
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::calculated;
+
+    fn divider(given: &[&str]) -> Result<Divider, &'static str> {
+        calculated(given, Divider::new, Divider::calc)
+    }
+
+    #[test]
+    fn loaded_divider() {
+        // 5 mA through the load and ten times that through R2
+        let divider = divider(&["uin=12V", "uout=5V", "rl=1000ohm"]).unwrap();
+        assert!((divider.i_cross.unwrap() - 50e-3f64).abs() < 1e-12);
+        assert!((divider.r2.unwrap() - 100f64).abs() < 1e-9);
+        assert!((divider.r1.unwrap() - 7f64 / 55e-3f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn given_cross_current() {
+        let divider = divider(&["uin=10V", "uout=2V", "iq=1mA"]).unwrap();
+        assert!((divider.r2.unwrap() - 2000f64).abs() < 1e-9);
+        assert!((divider.r1.unwrap() - 8000f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn strange_dividers() {
+        assert!(divider(&["uin=5V", "uout=12V", "iq=1mA"]).is_err());
+        assert!(divider(&["uin=12V", "uout=5V"]).is_err());
+        assert!(divider(&["uin=12V", "uout=5V", "rl=1000ohm", "il=1mA"]).is_err());
+    }
+}
@@ -0,0 +1,305 @@
+// the preferred values of the IEC 60063 E-series. every calculated component value is a float,
+// but we can only buy the values of these series
+use std::f64;
+
+use super::{parse_value, split_given};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ESeries {
+    E6,
+    E12,
+    E24,
+    E48,
+    E96,
+    E192,
+}
+impl ESeries {
+    pub fn new(series: &str) -> Result<ESeries, &'static str> {
+        match series.to_lowercase().as_str() {
+            "e6" => Ok(ESeries::E6),
+            "e12" => Ok(ESeries::E12),
+            "e24" => Ok(ESeries::E24),
+            "e48" => Ok(ESeries::E48),
+            "e96" => Ok(ESeries::E96),
+            "e192" => Ok(ESeries::E192),
+            _ => Err("e-series not known"),
+        }
+    }
+    // the values of one decade from 1 to below 10
+    pub fn values(&self) -> &'static [f64] {
+        match self {
+            ESeries::E6 => &E6,
+            ESeries::E12 => &E12,
+            ESeries::E24 => &E24,
+            ESeries::E48 => &E48,
+            ESeries::E96 => &E96,
+            ESeries::E192 => &E192,
+        }
+    }
+    // all preferred values between low and high. we work with the three digits as whole number
+    // and divide by the power of ten, this keeps values like 0.0047 free of rounding noise
+    fn candidates(&self, low: f64, high: f64) -> Vec<f64> {
+        let mut candidates: Vec<f64> = Vec::new();
+        if !low.is_finite() || !high.is_finite() || low <= 0f64 || high < low {
+            return candidates;
+        }
+        for pow in (low.log10().floor() as i32)..(high.log10().floor() as i32 + 1) {
+            for base in self.values().iter() {
+                let digits = (base * 100f64).round();
+                let candidate = if pow >= 2 {
+                    digits * 10f64.powi(pow - 2)
+                } else {
+                    digits / 10f64.powi(2 - pow)
+                };
+                if candidate >= low && candidate <= high {
+                    candidates.push(candidate);
+                }
+            }
+        }
+        candidates
+    }
+    // snaps the value to the nearest preferred value. nearest means the smallest ratio, because
+    // the series are logarithmic
+    pub fn nearest(&self, value: f64) -> f64 {
+        if !value.is_finite() || value <= 0f64 {
+            return f64::NAN;
+        }
+        // the neighbour decades are needed for values like 9.9 which are nearer to 10 than to 8.2
+        let mut best = f64::NAN;
+        let mut best_ratio = f64::INFINITY;
+        for candidate in self.candidates(value / 10f64, value * 10f64).iter() {
+            let ratio = (candidate / value).ln().abs();
+            if ratio < best_ratio {
+                best_ratio = ratio;
+                best = *candidate;
+            }
+        }
+        best
+    }
+    // the best two values in series for the target, gives back (Ra, Rb, Ra + Rb)
+    pub fn best_series_pair(&self, target: f64) -> (f64, f64, f64) {
+        let mut best = (f64::NAN, f64::NAN, f64::NAN);
+        let mut best_error = f64::INFINITY;
+        // the bigger of the two is at least the half of the target
+        for a in self.candidates(target / 2f64, target).iter() {
+            let b = self.nearest(target - a);
+            // a single value is also a solution, then b is zero
+            let (b, total) = if target - a > 0f64 && !b.is_nan() {
+                (b, a + b)
+            } else {
+                (0f64, *a)
+            };
+            let error = (total - target).abs();
+            if error < best_error {
+                best_error = error;
+                best = (*a, b, total);
+            }
+        }
+        best
+    }
+    // the best two values in parallel for the target, gives back (Ra, Rb, Ra || Rb)
+    pub fn best_parallel_pair(&self, target: f64) -> (f64, f64, f64) {
+        let mut best = (f64::NAN, f64::NAN, f64::NAN);
+        let mut best_error = f64::INFINITY;
+        // the smaller of the two is between the target and the double of it
+        for a in self.candidates(target, target * 2f64).iter() {
+            let rest = 1f64 / (1f64 / target - 1f64 / a);
+            let (b, total) = if rest.is_finite() && rest > 0f64 {
+                let b = self.nearest(rest);
+                (b, a * b / (a + b))
+            } else {
+                (f64::INFINITY, *a)
+            };
+            let error = (total - target).abs();
+            if error < best_error {
+                best_error = error;
+                best = (*a, b, total);
+            }
+        }
+        best
+    }
+    // the best pair for a voltage divider with the ratio Uout/Uin = R2/(R1 + R2). both values are
+    // searched around the wanted total resistance, the first criterion is the error of the ratio
+    // and the second one the error of the total resistance. gives back (R1, R2, actual ratio)
+    pub fn best_divider(&self, ratio: f64, r_total: f64) -> (f64, f64, f64) {
+        let mut best = (f64::NAN, f64::NAN, f64::NAN);
+        let mut best_error = (f64::INFINITY, f64::INFINITY);
+        if !(ratio > 0f64 && ratio < 1f64) {
+            return best;
+        }
+        let r2_candidates = self.candidates(r_total * ratio / 10f64, r_total * ratio * 10f64);
+        for r2 in r2_candidates.iter() {
+            let r1 = self.nearest(r2 * (1f64 - ratio) / ratio);
+            let actual = r2 / (r1 + r2);
+            let error = (
+                ((actual - ratio).abs() / ratio * 1e9).round(),
+                ((r1 + r2) / r_total).ln().abs(),
+            );
+            if error < best_error {
+                best_error = error;
+                best = (r1, *r2, actual);
+            }
+        }
+        best
+    }
+}
+
+// the input for the preferred value tool. a target value is snapped and combined from two values,
+// a ratio gives the best voltage divider
+#[derive(Clone, Debug)]
+pub struct Preferred {
+    series: ESeries,
+    value: Option<f64>,   //ohm
+    ratio: Option<f64>,   //Uout/Uin
+    r_total: Option<f64>, //ohm for the divider
+}
+impl Preferred {
+    pub fn new(s: Vec<String>) -> Result<Preferred, &'static str> {
+        let mut preferred = Preferred {
+            series: ESeries::E24,
+            value: None,
+            ratio: None,
+            r_total: None,
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "r" | "value" => preferred.value = Some(parse_value(&value, &["ohm", "Ω"])?),
+                "ratio" => preferred.ratio = Some(parse_value(&value, &[])?),
+                "rtotal" => preferred.r_total = Some(parse_value(&value, &["ohm", "Ω"])?),
+                "series" | "" => preferred.series = ESeries::new(&value)?,
+                _ => return Err("input is strange"),
+            }
+        }
+        if let Some(ratio) = preferred.ratio {
+            if !(ratio > 0f64 && ratio < 1f64) {
+                return Err("the ratio has to be between 0 and 1");
+            }
+        }
+        if preferred.value.is_none() && preferred.ratio.is_none() {
+            return Err("give me a value or a ratio");
+        }
+        Ok(preferred)
+    }
+    pub fn calc(&self, should_print: bool) {
+        if !should_print {
+            return;
+        }
+        let error = |actual: f64, target: f64| (actual - target) / target * 100f64;
+        if let Some(value) = self.value {
+            let nearest = self.series.nearest(value);
+            println!(
+                "The nearest {:?} value for {} Ω is: {} Ω ({:+.3} %)",
+                self.series,
+                value,
+                nearest,
+                error(nearest, value)
+            );
+            let (a, b, total) = self.series.best_series_pair(value);
+            println!(
+                "The best series pair is: {} Ω + {} Ω = {} Ω ({:+.3} %)",
+                a,
+                b,
+                total,
+                error(total, value)
+            );
+            let (a, b, total) = self.series.best_parallel_pair(value);
+            println!(
+                "The best parallel pair is: {} Ω || {} Ω = {} Ω ({:+.3} %)",
+                a,
+                b,
+                total,
+                error(total, value)
+            );
+        }
+        if let Some(ratio) = self.ratio {
+            // without a total resistance the divider is searched around 10 kΩ
+            let r_total = self.r_total.or(self.value).unwrap_or(10e3f64);
+            let (r1, r2, actual) = self.series.best_divider(ratio, r_total);
+            println!(
+                "The best divider for the ratio {} is: R1 = {} Ω and R2 = {} Ω with the ratio {} ({:+.3} %)",
+                ratio,
+                r1,
+                r2,
+                actual,
+                error(actual, ratio)
+            );
+        }
+    }
+}
+
+static E6: [f64; 6] = [1.0, 1.5, 2.2, 3.3, 4.7, 6.8];
+static E12: [f64; 12] = [1.0, 1.2, 1.5, 1.8, 2.2, 2.7, 3.3, 3.9, 4.7, 5.6, 6.8, 8.2];
+static E24: [f64; 24] = [
+    1.0, 1.1, 1.2, 1.3, 1.5, 1.6, 1.8, 2.0, 2.2, 2.4, 2.7, 3.0, 3.3, 3.6, 3.9, 4.3, 4.7, 5.1, 5.6,
+    6.2, 6.8, 7.5, 8.2, 9.1,
+];
+// the series from E48 on have three digits and are not all part of each other like E6 to E24
+static E48: [f64; 48] = [
+    1.00, 1.05, 1.10, 1.15, 1.21, 1.27, 1.33, 1.40, 1.47, 1.54, 1.62, 1.69, 1.78, 1.87, 1.96, 2.05,
+    2.15, 2.26, 2.37, 2.49, 2.61, 2.74, 2.87, 3.01, 3.16, 3.32, 3.48, 3.65, 3.83, 4.02, 4.22, 4.42,
+    4.64, 4.87, 5.11, 5.36, 5.62, 5.90, 6.19, 6.49, 6.81, 7.15, 7.50, 7.87, 8.25, 8.66, 9.09, 9.53,
+];
+static E96: [f64; 96] = [
+    1.00, 1.02, 1.05, 1.07, 1.10, 1.13, 1.15, 1.18, 1.21, 1.24, 1.27, 1.30, 1.33, 1.37, 1.40, 1.43,
+    1.47, 1.50, 1.54, 1.58, 1.62, 1.65, 1.69, 1.74, 1.78, 1.82, 1.87, 1.91, 1.96, 2.00, 2.05, 2.10,
+    2.15, 2.21, 2.26, 2.32, 2.37, 2.43, 2.49, 2.55, 2.61, 2.67, 2.74, 2.80, 2.87, 2.94, 3.01, 3.09,
+    3.16, 3.24, 3.32, 3.40, 3.48, 3.57, 3.65, 3.74, 3.83, 3.92, 4.02, 4.12, 4.22, 4.32, 4.42, 4.53,
+    4.64, 4.75, 4.87, 4.99, 5.11, 5.23, 5.36, 5.49, 5.62, 5.76, 5.90, 6.04, 6.19, 6.34, 6.49, 6.65,
+    6.81, 6.98, 7.15, 7.32, 7.50, 7.68, 7.87, 8.06, 8.25, 8.45, 8.66, 8.87, 9.09, 9.31, 9.53, 9.76,
+];
+static E192: [f64; 192] = [
+    1.00, 1.01, 1.02, 1.04, 1.05, 1.06, 1.07, 1.09, 1.10, 1.11, 1.13, 1.14, 1.15, 1.17, 1.18, 1.20,
+    1.21, 1.23, 1.24, 1.26, 1.27, 1.29, 1.30, 1.32, 1.33, 1.35, 1.37, 1.38, 1.40, 1.42, 1.43, 1.45,
+    1.47, 1.49, 1.50, 1.52, 1.54, 1.56, 1.58, 1.60, 1.62, 1.64, 1.65, 1.67, 1.69, 1.72, 1.74, 1.76,
+    1.78, 1.80, 1.82, 1.84, 1.87, 1.89, 1.91, 1.93, 1.96, 1.98, 2.00, 2.03, 2.05, 2.08, 2.10, 2.13,
+    2.15, 2.18, 2.21, 2.23, 2.26, 2.29, 2.32, 2.34, 2.37, 2.40, 2.43, 2.46, 2.49, 2.52, 2.55, 2.58,
+    2.61, 2.64, 2.67, 2.71, 2.74, 2.77, 2.80, 2.84, 2.87, 2.91, 2.94, 2.98, 3.01, 3.05, 3.09, 3.12,
+    3.16, 3.20, 3.24, 3.28, 3.32, 3.36, 3.40, 3.44, 3.48, 3.52, 3.57, 3.61, 3.65, 3.70, 3.74, 3.79,
+    3.83, 3.88, 3.92, 3.97, 4.02, 4.07, 4.12, 4.17, 4.22, 4.27, 4.32, 4.37, 4.42, 4.48, 4.53, 4.59,
+    4.64, 4.70, 4.75, 4.81, 4.87, 4.93, 4.99, 5.05, 5.11, 5.17, 5.23, 5.30, 5.36, 5.42, 5.49, 5.56,
+    5.62, 5.69, 5.76, 5.83, 5.90, 5.97, 6.04, 6.12, 6.19, 6.26, 6.34, 6.42, 6.49, 6.57, 6.65, 6.73,
+    6.81, 6.90, 6.98, 7.06, 7.15, 7.23, 7.32, 7.41, 7.50, 7.59, 7.68, 7.77, 7.87, 7.96, 8.06, 8.16,
+    8.25, 8.35, 8.45, 8.56, 8.66, 8.76, 8.87, 8.98, 9.09, 9.20, 9.31, 9.42, 9.53, 9.65, 9.76, 9.88,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn series_lengths() {
+        for (name, length) in [("E6", 6), ("e12", 12), ("E24", 24), ("E48", 48), ("E96", 96)] {
+            assert_eq!(ESeries::new(name).unwrap().values().len(), length);
+        }
+        assert_eq!(ESeries::E192.values().len(), 192);
+        assert!(ESeries::new("e7").is_err());
+    }
+
+    #[test]
+    fn nearest_value() {
+        assert_eq!(ESeries::E24.nearest(4700f64), 4700f64);
+        assert_eq!(ESeries::E12.nearest(0.0047f64), 0.0047f64);
+        // 9.9 is nearer to the 10 of the next decade
+        assert_eq!(ESeries::E12.nearest(9.9f64), 10f64);
+        assert_eq!(ESeries::E6.nearest(1.8e3f64), 1.5e3f64);
+        assert!(ESeries::E24.nearest(-1f64).is_nan());
+        assert!(ESeries::E24.nearest(0f64).is_nan());
+    }
+
+    #[test]
+    fn pairs() {
+        assert_eq!(ESeries::E24.best_series_pair(1000f64).2, 1000f64);
+        let (a, b, total) = ESeries::E24.best_series_pair(1234f64);
+        assert!(ESeries::E24.nearest(a) == a && ESeries::E24.nearest(b) == b);
+        assert!((total - 1234f64).abs() <= 4f64);
+        let (_, _, total) = ESeries::E24.best_parallel_pair(500f64);
+        assert!((total - 500f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn divider() {
+        assert_eq!(ESeries::E24.best_divider(0.5f64, 10e3f64), (5100f64, 5100f64, 0.5f64));
+        assert!(ESeries::E24.best_divider(1.5f64, 10e3f64).0.is_nan());
+    }
+}
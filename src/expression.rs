@@ -0,0 +1,435 @@
+// arithmetic with units, like 2*15m + 3m, pi*(0.8mm)^2/4 or 60W/230V. the expression is read
+// from left to right by a small recursive descent parser:
+//   sum     = product (('+' | '-') product)*
+//   product = sign (('*' | '/') sign)*
+//   sign    = ('+' | '-') sign | power
+//   power   = factor ('^' sign)?        so 2^-1 works and 2^3^2 is 2^(3^2)
+//   factor  = number unit? | name '(' sum (',' sum)* ')' | name | '(' sum ')'   followed by ² or ³
+// a unit directly after a number belongs to the number, so 0.8mm^2 is 0.8 mm². a name alone is a
+// variable, a constant or a unit
+use std::f64;
+
+use super::PreUnits;
+use quantity::{Dimension, Quantity};
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Operator(char),
+    Power(i8), // ² and ³
+}
+
+// variables which refer to each other could go on forever
+const MAX_DEPTH: usize = 16;
+
+// the rkm code of IEC 60062 like 4k7 or 2R2 and a lone pre unit like 1k after the digits from
+// start to k, as in parse_value. the number and where it ends, None if the letters are a unit like
+// the m² of 2m2 or the tesla of 1T
+fn rkm(chars: &[char], start: usize, k: usize) -> Option<(f64, usize)> {
+    let pre_unit = *chars.get(k)?;
+    if chars[start..k].contains(&'.') || !"PTGMkmuµnpR".contains(pre_unit) {
+        return None;
+    }
+    let mut end = k + 1;
+    while end < chars.len() && chars[end].is_ascii_digit() {
+        end += 1;
+    }
+    // a lone pre unit is only one if no unit follows, 1kohm is read by the units
+    if end == k + 1 && chars.get(end).is_some_and(|c| c.is_alphabetic()) {
+        return None;
+    }
+    let symbol: String = chars[k..end].iter().collect();
+    if Quantity::unit(&symbol).is_some() {
+        return None;
+    }
+    let digits: String = chars[start..k].iter().collect();
+    let decimals: String = chars[k + 1..end].iter().collect();
+    let number = format!("{}.{}", digits, decimals).parse::<f64>().ok()?;
+    let pre_unit = if pre_unit == 'R' { "".to_string() } else { pre_unit.to_string() };
+    Some((PreUnits::new(pre_unit).to_base(number), end))
+}
+
+fn tokens(input: &str) -> Result<Vec<Token>, &'static str> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut k = 0;
+    while k < chars.len() {
+        let c = chars[k];
+        if c.is_whitespace() {
+            k += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = k;
+            while k < chars.len() && (chars[k].is_ascii_digit() || chars[k] == '.') {
+                k += 1;
+            }
+            if let Some((number, end)) = rkm(&chars, start, k) {
+                tokens.push(Token::Number(number));
+                k = end;
+                continue;
+            }
+            // the exponent like 1e-3, but 2e alone is 2 times e
+            if k < chars.len() && (chars[k] == 'e' || chars[k] == 'E') {
+                let mut end = k + 1;
+                if end < chars.len() && (chars[end] == '-' || chars[end] == '+') {
+                    end += 1;
+                }
+                if end < chars.len() && chars[end].is_ascii_digit() {
+                    k = end;
+                    while k < chars.len() && chars[k].is_ascii_digit() {
+                        k += 1;
+                    }
+                }
+            }
+            let number: String = chars[start..k].iter().collect();
+            match number.parse::<f64>() {
+                Ok(number) => tokens.push(Token::Number(number)),
+                Err(_) => return Err("number not parseable"),
+            }
+        } else if c.is_alphabetic() || c == '_' || c == '$' || c == '%' {
+            let start = k;
+            k += 1;
+            // % is a unit on its own, or the start of %IACS
+            if c == '%' {
+                while k < chars.len() && chars[k].is_ascii_uppercase() {
+                    k += 1;
+                }
+            } else {
+                while k < chars.len()
+                    && (chars[k].is_alphabetic() || chars[k].is_ascii_digit() || chars[k] == '_')
+                {
+                    k += 1;
+                }
+            }
+            tokens.push(Token::Name(chars[start..k].iter().collect()));
+        } else {
+            tokens.push(match c {
+                '+' | '-' | '*' | '/' | '^' | '(' | ')' | ',' => Token::Operator(c),
+                '·' | '×' => Token::Operator('*'),
+                '÷' => Token::Operator('/'),
+                '²' => Token::Power(2),
+                '³' => Token::Power(3),
+                _ => return Err("character not known in the expression"),
+            });
+            k += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+fn constant(name: &str) -> Option<Quantity> {
+    match name {
+        "pi" | "π" => Some(Quantity::number(f64::consts::PI)),
+        "e" => Some(Quantity::number(f64::consts::E)),
+        // the magnetic and electric constant in H/m and F/m
+        "mu0" | "µ0" | "μ0" => Some(Quantity::new(
+            4e-7f64 * f64::consts::PI,
+            Dimension([1, 1, -2, -2, 0]),
+        )),
+        "eps0" | "ε0" => Some(Quantity::new(
+            8.8541878128e-12f64,
+            Dimension([-3, -1, 4, 2, 0]),
+        )),
+        _ => None,
+    }
+}
+
+fn function(name: &str, arguments: &[Quantity]) -> Result<Quantity, &'static str> {
+    let one = || {
+        if arguments.len() == 1 {
+            Ok(arguments[0])
+        } else {
+            Err("this function takes one value")
+        }
+    };
+    // sin, log and the others only work on numbers without unit
+    let number = || {
+        let argument = one()?;
+        if argument.is_dimensionless() {
+            Ok(argument.value)
+        } else {
+            Err("this function takes a value without unit")
+        }
+    };
+    Ok(match name {
+        "sqrt" => one()?.root(2)?,
+        "cbrt" => one()?.root(3)?,
+        "abs" => {
+            let argument = one()?;
+            Quantity::new(argument.value.abs(), argument.dimension)
+        }
+        "min" | "max" => {
+            if arguments.is_empty() {
+                return Err("min and max need at least one value");
+            }
+            let mut extreme = arguments[0];
+            for argument in arguments.iter() {
+                // minus checks the units
+                let difference = argument.minus(&extreme)?.value;
+                if (name == "min" && difference < 0f64) || (name == "max" && difference > 0f64) {
+                    extreme = *argument;
+                }
+            }
+            extreme
+        }
+        "log" => Quantity::number(number()?.log10()),
+        "ln" => Quantity::number(number()?.ln()),
+        "exp" => Quantity::number(number()?.exp()),
+        "sin" => Quantity::number(number()?.sin()),
+        "cos" => Quantity::number(number()?.cos()),
+        "tan" => Quantity::number(number()?.tan()),
+        "asin" => Quantity::number(number()?.asin()),
+        "acos" => Quantity::number(number()?.acos()),
+        "atan" => Quantity::number(number()?.atan()),
+        _ => return Err("function not known"),
+    })
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    variables: &'a dyn Fn(&str) -> Option<String>,
+    depth: usize,
+}
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+    fn is_operator(&self, operator: char) -> bool {
+        self.peek() == Some(&Token::Operator(operator))
+    }
+    fn sum(&mut self) -> Result<Quantity, &'static str> {
+        let mut result = self.product()?;
+        loop {
+            if self.is_operator('+') {
+                self.position += 1;
+                result = result.plus(&self.product()?)?;
+            } else if self.is_operator('-') {
+                self.position += 1;
+                result = result.minus(&self.product()?)?;
+            } else {
+                return Ok(result);
+            }
+        }
+    }
+    fn product(&mut self) -> Result<Quantity, &'static str> {
+        let mut result = self.sign()?;
+        loop {
+            if self.is_operator('*') {
+                self.position += 1;
+                result = result.times(&self.sign()?)?;
+            } else if self.is_operator('/') {
+                self.position += 1;
+                result = result.divided(&self.sign()?)?;
+            } else {
+                return Ok(result);
+            }
+        }
+    }
+    fn sign(&mut self) -> Result<Quantity, &'static str> {
+        if self.is_operator('-') {
+            self.position += 1;
+            return Ok(-self.sign()?);
+        }
+        if self.is_operator('+') {
+            self.position += 1;
+            return self.sign();
+        }
+        self.power()
+    }
+    fn power(&mut self) -> Result<Quantity, &'static str> {
+        let base = self.factor()?;
+        if self.is_operator('^') {
+            self.position += 1;
+            let exponent = self.sign()?;
+            return base.pow(&exponent);
+        }
+        Ok(base)
+    }
+    // ² and ³ after a factor
+    fn superscripts(&mut self, mut result: Quantity) -> Result<Quantity, &'static str> {
+        while let Some(Token::Power(n)) = self.peek().cloned() {
+            self.position += 1;
+            result = result.pow(&Quantity::number(n as f64))?;
+        }
+        Ok(result)
+    }
+    fn factor(&mut self) -> Result<Quantity, &'static str> {
+        let result = match self.next() {
+            Some(Token::Number(number)) => {
+                let mut number = Quantity::number(number);
+                // the units directly after the number, each with its own exponent, like 100 %IACS
+                loop {
+                    let unit = match self.peek() {
+                        Some(Token::Name(name)) => Quantity::unit(name),
+                        _ => None,
+                    };
+                    match unit {
+                        Some(unit) => {
+                            self.position += 1;
+                            let mut unit = self.superscripts(unit)?;
+                            if self.is_operator('^') {
+                                self.position += 1;
+                                let exponent = self.sign()?;
+                                unit = unit.pow(&exponent)?;
+                            }
+                            number = number.times(&unit)?;
+                        }
+                        None => break number,
+                    }
+                }
+            }
+            Some(Token::Name(name)) => {
+                if self.is_operator('(') {
+                    self.position += 1;
+                    let mut arguments = vec![self.sum()?];
+                    while self.is_operator(',') {
+                        self.position += 1;
+                        arguments.push(self.sum()?);
+                    }
+                    if !self.is_operator(')') {
+                        return Err("a bracket is missing");
+                    }
+                    self.position += 1;
+                    function(&name, &arguments)?
+                } else {
+                    self.name(&name)?
+                }
+            }
+            Some(Token::Operator('(')) => {
+                let result = self.sum()?;
+                if !self.is_operator(')') {
+                    return Err("a bracket is missing");
+                }
+                self.position += 1;
+                result
+            }
+            _ => return Err("expression is strange"),
+        };
+        self.superscripts(result)
+    }
+    // a variable, a constant or a unit
+    fn name(&self, name: &str) -> Result<Quantity, &'static str> {
+        if let Some(value) = (self.variables)(name) {
+            if self.depth >= MAX_DEPTH {
+                return Err("the variables refer to each other too deep");
+            }
+            return evaluate_deep(&value, self.variables, self.depth + 1)
+                .map_err(|_| "a variable in the expression has no value with a unit");
+        }
+        if let Some(constant) = constant(name) {
+            return Ok(constant);
+        }
+        if let Some(unit) = Quantity::unit(name) {
+            return Ok(unit);
+        }
+        Err("name not known in the expression")
+    }
+}
+
+fn evaluate_deep(
+    input: &str,
+    variables: &dyn Fn(&str) -> Option<String>,
+    depth: usize,
+) -> Result<Quantity, &'static str> {
+    let mut parser = Parser {
+        tokens: tokens(input)?,
+        position: 0,
+        variables,
+        depth,
+    };
+    let result = parser.sum()?;
+    if parser.position < parser.tokens.len() {
+        return Err("expression is strange");
+    }
+    if !result.value.is_finite() {
+        return Err("the expression has no finite value");
+    }
+    Ok(result)
+}
+
+// the value of the expression, variables are looked up with the given function
+pub fn evaluate(
+    input: &str,
+    variables: &dyn Fn(&str) -> Option<String>,
+) -> Result<Quantity, &'static str> {
+    evaluate_deep(input, variables, 0)
+}
+
+// the value of the expression in the given unit, like 1.5 for pi*(0.69mm)^2 in mm2. without unit
+// the expression has to be without unit too
+pub fn evaluate_in(input: &str, unit: &str) -> Result<f64, &'static str> {
+    let result = evaluate(input, &|_| None)?;
+    let unit = if unit.is_empty() {
+        Quantity::number(1f64)
+    } else {
+        match evaluate(unit, &|_| None) {
+            Ok(unit) => unit,
+            Err(_) => return Err("the unit can't be calculated with"),
+        }
+    };
+    result.in_unit(&unit)
+}
+
+// if the input is meant as expression and not as a single value
+pub fn is_expression(input: &str) -> bool {
+    let input = input.trim();
+    input.contains(|c: char| "+*/^()²³·×÷".contains(c))
+        || input.chars().skip(1).any(|c| c == '-')
+        || constant(input).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(input: &str) -> Quantity {
+        evaluate(input, &|_| None).unwrap()
+    }
+
+    #[test]
+    fn rkm_literals() {
+        assert!((value("4k7+1k").value - 5700f64).abs() < 1e-9);
+        assert!((value("2R2*2").value - 4.4f64).abs() < 1e-12);
+        assert!((value("4k7ohm").value - 4700f64).abs() < 1e-9);
+        // m2 and T stay units
+        assert_eq!(value("2m2 + 0m2").dimension, Dimension([2, 0, 0, 0, 0]));
+        assert_eq!(value("1T*1").dimension, Dimension([0, 1, -2, -1, 0]));
+    }
+
+    #[test]
+    fn units_and_precedence() {
+        assert!((value("2*15m + 3m").value - 33f64).abs() < 1e-12);
+        assert!((value("2^3^2").value - 512f64).abs() < 1e-9);
+        assert!((value("-2^2").value + 4f64).abs() < 1e-12);
+        assert!((evaluate_in("pi*(0.69mm)^2/4", "mm2").unwrap() - 0.3739f64).abs() < 1e-4);
+        assert!(evaluate("1m + 1V", &|_| None).is_err());
+        assert!(evaluate("(1m", &|_| None).is_err());
+    }
+
+    #[test]
+    fn variables_refer_to_each_other() {
+        let variables = |name: &str| match name {
+            "l" => Some("2*w".to_string()),
+            "w" => Some("l".to_string()),
+            "a" => Some("3mm".to_string()),
+            _ => None,
+        };
+        assert!((evaluate("a*2", &variables).unwrap().value - 6e-3f64).abs() < 1e-15);
+        assert!(evaluate("l", &variables).is_err());
+    }
+
+    #[test]
+    fn results_read_well() {
+        assert_eq!(value("ε0").pretty(), "8.854 pF/m");
+        assert_eq!(value("mu0").pretty(), "1.257 µH/m");
+        assert_eq!(value("2^1000").pretty(), "1.07151e301");
+        assert_eq!(value("60W/230V").pretty(), "260.9 mA");
+        assert!(is_expression("2*l") && !is_expression("4k7") && !is_expression("-3V"));
+    }
+}
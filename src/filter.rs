@@ -0,0 +1,322 @@
+// design of passive filters. from the type, the topology and the cutoff or centre frequency the
+// component values are calculated, snapped to an e-series and the real filter is analysed again
+use std::f64;
+
+use super::{parse_value, split_given};
+use eseries::ESeries;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FilterType {
+    LowPass,
+    HighPass,
+    BandPass,
+    BandStop,
+}
+
+// rc: first order with the capacitor as shunt (low pass) or in series (high pass)
+// lc: second order L-section loaded with r, for low and high pass
+// rlc: series resonant circuit, the output is across r (band pass) or across l and c (band stop)
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Topology {
+    RC,
+    LC,
+    Rlc,
+}
+
+#[derive(Clone, Debug)]
+pub struct Filter {
+    filter_type: FilterType,
+    topology: Topology,
+    series: ESeries,
+    f0: Option<f64>, //Hz cutoff or centre frequency
+    q: Option<f64>,
+    bw: Option<f64>, //Hz bandwidth
+    r: Option<f64>,  //ohm
+    l: Option<f64>,  //H
+    c: Option<f64>,  //F
+    frequencies: Vec<f64>, //Hz where the attenuation is reported
+}
+impl Filter {
+    pub fn new(s: Vec<String>) -> Result<Filter, &'static str> {
+        let mut filter_type: Option<FilterType> = None;
+        let mut topology: Option<Topology> = None;
+        let mut filter = Filter {
+            filter_type: FilterType::LowPass,
+            topology: Topology::RC,
+            series: ESeries::E24,
+            f0: None,
+            q: None,
+            bw: None,
+            r: None,
+            l: None,
+            c: None,
+            frequencies: Vec::new(),
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "fc" | "f0" => filter.f0 = Some(parse_value(&value, &["Hz"])?),
+                "q" => filter.q = Some(parse_value(&value, &[])?),
+                "bw" | "b" => filter.bw = Some(parse_value(&value, &["Hz"])?),
+                "r" => filter.r = Some(parse_value(&value, &["ohm", "Ω"])?),
+                "l" => filter.l = Some(parse_value(&value, &["H"])?),
+                "c" => filter.c = Some(parse_value(&value, &["F"])?),
+                "f" => filter.frequencies.push(parse_value(&value, &["Hz"])?),
+                "series" => filter.series = ESeries::new(&value)?,
+                "" => match value.to_lowercase().as_str() {
+                    "lowpass" | "tiefpass" | "lp" => filter_type = Some(FilterType::LowPass),
+                    "highpass" | "hochpass" | "hp" => filter_type = Some(FilterType::HighPass),
+                    "bandpass" | "bp" => filter_type = Some(FilterType::BandPass),
+                    "bandstop" | "bandsperre" | "notch" | "bs" => {
+                        filter_type = Some(FilterType::BandStop)
+                    }
+                    "rc" => topology = Some(Topology::RC),
+                    "lc" => topology = Some(Topology::LC),
+                    "rlc" => topology = Some(Topology::Rlc),
+                    "e6" | "e12" | "e24" | "e48" | "e96" | "e192" => {
+                        filter.series = ESeries::new(&value)?
+                    }
+                    _ => return Err("filter type or topology not known"),
+                },
+                _ => return Err("input is strange"),
+            }
+        }
+        filter.filter_type = if let Some(filter_type) = filter_type {
+            filter_type
+        } else {
+            return Err("give me the filter type");
+        };
+        // without a topology the simplest one for the type is used
+        filter.topology = if let Some(topology) = topology {
+            topology
+        } else {
+            match filter.filter_type {
+                FilterType::LowPass | FilterType::HighPass => Topology::RC,
+                FilterType::BandPass | FilterType::BandStop => Topology::Rlc,
+            }
+        };
+        match (filter.filter_type, filter.topology) {
+            (FilterType::LowPass, Topology::Rlc)
+            | (FilterType::HighPass, Topology::Rlc)
+            | (FilterType::BandPass, Topology::RC)
+            | (FilterType::BandStop, Topology::RC) => {
+                return Err("this topology can't build this filter type")
+            }
+            _ => {}
+        }
+        // lc and rlc band filters are the same circuit, the r is the load
+        if filter.topology == Topology::LC {
+            if let FilterType::BandPass | FilterType::BandStop = filter.filter_type {
+                filter.topology = Topology::Rlc;
+            }
+        }
+        if filter.f0.is_none() {
+            return Err("give me the cutoff or centre frequency");
+        }
+        Ok(filter)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        let f0 = self.f0.unwrap_or(f64::NAN);
+        let omega0 = 2f64 * f64::consts::PI * f0;
+        match self.topology {
+            // fc = 1/(2π·R·C)
+            Topology::RC => match (self.r, self.c) {
+                (Some(r), None) => self.c = Some(1f64 / (omega0 * r)),
+                (None, Some(c)) => self.r = Some(1f64 / (omega0 * c)),
+                (Some(_), Some(_)) => return Err("give me only one of R and C"),
+                (None, None) => return Err("give me R or C"),
+            },
+            // ω0 = 1/√(L·C) and Q = R·√(C/L). without q the filter is a butterworth filter
+            Topology::LC => {
+                let r = if let Some(r) = self.r {
+                    r
+                } else {
+                    return Err("give me the load resistance R");
+                };
+                let q = if let Some(q) = self.q { q } else { 1f64 / 2f64.sqrt() };
+                self.q = Some(q);
+                let c = q / (r * omega0);
+                self.c = Some(c);
+                self.l = Some(1f64 / (omega0 * omega0 * c));
+            }
+            // ω0 = 1/√(L·C) and Q = √(L/C)/R = f0/B
+            Topology::Rlc => {
+                let r = if let Some(r) = self.r {
+                    r
+                } else {
+                    return Err("give me the resistance R");
+                };
+                let q = if let Some(q) = self.q {
+                    q
+                } else if let Some(bw) = self.bw {
+                    f0 / bw
+                } else {
+                    return Err("give me Q or the bandwidth");
+                };
+                self.q = Some(q);
+                let l = q * r / omega0;
+                self.l = Some(l);
+                self.c = Some(1f64 / (omega0 * omega0 * l));
+            }
+        }
+        if should_print {
+            self.print_res()
+        }
+        Ok(())
+    }
+    // the snapped filter, the load resistance of the lc filter is given and not snapped
+    fn snapped(&self) -> Filter {
+        let mut snapped = self.clone();
+        snapped.r = if self.topology == Topology::LC {
+            self.r
+        } else {
+            self.r.map(|r| self.series.nearest(r))
+        };
+        snapped.l = self.l.map(|l| self.series.nearest(l));
+        snapped.c = self.c.map(|c| self.series.nearest(c));
+        snapped
+    }
+    fn r(&self) -> f64 {
+        self.r.unwrap_or(f64::NAN)
+    }
+    fn l(&self) -> f64 {
+        self.l.unwrap_or(f64::NAN)
+    }
+    fn c(&self) -> f64 {
+        self.c.unwrap_or(f64::NAN)
+    }
+    // cutoff or centre frequency from the component values
+    fn actual_f0(&self) -> f64 {
+        match self.topology {
+            Topology::RC => 1f64 / (2f64 * f64::consts::PI * self.r() * self.c()),
+            Topology::LC | Topology::Rlc => {
+                1f64 / (2f64 * f64::consts::PI * (self.l() * self.c()).sqrt())
+            }
+        }
+    }
+    fn actual_q(&self) -> f64 {
+        match self.topology {
+            Topology::RC => f64::NAN,
+            Topology::LC => self.r() * (self.c() / self.l()).sqrt(),
+            Topology::Rlc => (self.l() / self.c()).sqrt() / self.r(),
+        }
+    }
+    // the amount of the transfer function at the frequency f
+    fn gain(&self, f: f64) -> f64 {
+        let omega = 2f64 * f64::consts::PI * f;
+        let (r, l, c) = (self.r(), self.l(), self.c());
+        match (self.topology, self.filter_type) {
+            (Topology::RC, FilterType::LowPass) => 1f64 / (1f64 + (omega * r * c).powi(2)).sqrt(),
+            (Topology::RC, FilterType::HighPass) => {
+                omega * r * c / (1f64 + (omega * r * c).powi(2)).sqrt()
+            }
+            (Topology::LC, filter_type) => {
+                let denominator =
+                    ((1f64 - omega * omega * l * c).powi(2) + (omega * l / r).powi(2)).sqrt();
+                if filter_type == FilterType::LowPass {
+                    1f64 / denominator
+                } else {
+                    omega * omega * l * c / denominator
+                }
+            }
+            (Topology::Rlc, filter_type) => {
+                let x = omega * l - 1f64 / (omega * c);
+                if filter_type == FilterType::BandPass {
+                    r / (r * r + x * x).sqrt()
+                } else {
+                    x.abs() / (r * r + x * x).sqrt()
+                }
+            }
+            _ => f64::NAN,
+        }
+    }
+    fn print_components(&self) {
+        match self.topology {
+            Topology::RC => println!("R = {} Ω, C = {} F", self.r(), self.c()),
+            Topology::LC => println!(
+                "L = {} H, C = {} F (load R = {} Ω)",
+                self.l(),
+                self.c(),
+                self.r()
+            ),
+            Topology::Rlc => println!(
+                "R = {} Ω, L = {} H, C = {} F",
+                self.r(),
+                self.l(),
+                self.c()
+            ),
+        }
+    }
+    fn print_res(&self) {
+        let f0 = self.f0.unwrap_or(0f64);
+        println!("The calculated components for {} Hz are:", f0);
+        self.print_components();
+        let snapped = self.snapped();
+        println!("The components snapped to {:?} are:", self.series);
+        snapped.print_components();
+        println!("With them the real cutoff/centre frequency is: {} Hz", snapped.actual_f0());
+        if self.topology != Topology::RC {
+            println!("and the real Q is: {}", snapped.actual_q());
+        }
+        for f in self.frequencies.iter() {
+            println!(
+                "The attenuation at {} Hz is: {} dB",
+                f,
+                -20f64 * snapped.gain(*f).log10()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::calculated;
+
+    fn filter(given: &[&str]) -> Result<Filter, &'static str> {
+        calculated(given, Filter::new, Filter::calc)
+    }
+
+    #[test]
+    fn rc_low_pass() {
+        let filter = filter(&["lowpass", "fc=1kHz", "r=1kohm"]).unwrap();
+        assert!((filter.c() - 1f64 / (2f64 * f64::consts::PI * 1e6f64)).abs() < 1e-15);
+        assert!((filter.actual_f0() - 1000f64).abs() < 1e-9);
+        // -3 dB at the cutoff frequency
+        assert!((filter.gain(1000f64) - 0.5f64.sqrt()).abs() < 1e-12);
+        assert!(filter.gain(100f64) > 0.99f64);
+    }
+
+    #[test]
+    fn lc_butterworth() {
+        let filter = filter(&["lowpass", "lc", "fc=10kHz", "r=50ohm"]).unwrap();
+        assert!((filter.actual_q() - 0.5f64.sqrt()).abs() < 1e-12);
+        assert!((filter.gain(10e3f64) - 0.5f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rlc_band_filters() {
+        let band_pass = filter(&["bandpass", "f0=1kHz", "bw=100Hz", "r=100ohm"]).unwrap();
+        assert!((band_pass.actual_q() - 10f64).abs() < 1e-9);
+        assert!((band_pass.gain(1000f64) - 1f64).abs() < 1e-9);
+        let band_stop = filter(&["notch", "f0=1kHz", "q=5", "r=100ohm"]).unwrap();
+        assert!(band_stop.gain(1000f64) < 1e-6);
+    }
+
+    #[test]
+    fn snapped_to_the_series() {
+        let filter = filter(&["highpass", "fc=1kHz", "c=100nF", "e12"]).unwrap();
+        // 1.5915 kΩ is 1.5 kΩ in E12
+        assert!((filter.snapped().r() - 1500f64).abs() < 1e-9);
+        // the capacitor is already a value of the series
+        assert!((filter.snapped().c() - filter.c()).abs() < 1e-18);
+    }
+
+    #[test]
+    fn strange_filters() {
+        assert!(filter(&["lowpass", "rlc", "fc=1kHz", "r=1kohm"]).is_err());
+        assert!(filter(&["lowpass", "r=1kohm"]).is_err());
+        assert!(filter(&["lowpass", "fc=1kHz", "r=1kohm", "c=1uF"]).is_err());
+        assert!(filter(&["bandpass", "f0=1kHz", "r=1kohm"]).is_err());
+    }
+}
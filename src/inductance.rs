@@ -0,0 +1,260 @@
+// the inductance and capacitance of wires and simple geometries, all lengths in m:
+//   straight wire    L = mu0 l / (2 pi) * (ln(2 l / r) - 3/4)          (Rosa, low frequency)
+//   wire loop        L = mu0 R * (ln(8 R / r) - 7/4)                   R of the loop, r of the wire
+//   two wire line    L = mu0 l / pi * (acosh(s / d) + 1/4)    C = pi e0 er l / acosh(s / d)
+//   solenoid         L = D² N² / (18 D + 40 l) µH with D and l in inch (Wheeler, air core)
+//   multilayer coil  L = 0.8 a² N² / (6 a + 9 b + 10 c) µH in inch (Wheeler)
+//   plates           C = e0 er A / d
+//   coax             L = mu0 l / (2 pi) * ln(D / d)           C = 2 pi e0 er l / ln(D / d)
+use std::f64;
+
+use super::{format_value, parse_value, split_given};
+
+const MU0: f64 = 4e-7f64 * f64::consts::PI;
+const EPSILON0: f64 = 8.8541878128e-12f64;
+const INCH: f64 = 0.0254f64;
+
+pub fn straight_wire(length: f64, diameter: f64) -> f64 {
+    MU0 * length / (2f64 * f64::consts::PI) * ((4f64 * length / diameter).ln() - 0.75f64)
+}
+pub fn wire_loop(loop_diameter: f64, diameter: f64) -> f64 {
+    MU0 * loop_diameter / 2f64 * ((8f64 * loop_diameter / diameter).ln() - 1.75f64)
+}
+// the inductance of the loop of both wires and the capacitance between them
+pub fn two_wire(length: f64, diameter: f64, spacing: f64, er: f64) -> (f64, f64) {
+    let x = (spacing / diameter).acosh();
+    (
+        MU0 * length / f64::consts::PI * (x + 0.25f64),
+        f64::consts::PI * EPSILON0 * er * length / x,
+    )
+}
+pub fn solenoid(turns: f64, diameter: f64, length: f64) -> f64 {
+    let diameter = diameter / INCH;
+    let length = length / INCH;
+    diameter * diameter * turns * turns / (18f64 * diameter + 40f64 * length) * 1e-6f64
+}
+// a is the mean radius, b the length and c the depth of the winding
+pub fn multilayer(turns: f64, mean_diameter: f64, length: f64, depth: f64) -> f64 {
+    let radius = mean_diameter / 2f64 / INCH;
+    let length = length / INCH;
+    let depth = depth / INCH;
+    0.8f64 * radius * radius * turns * turns / (6f64 * radius + 9f64 * length + 10f64 * depth)
+        * 1e-6f64
+}
+pub fn plates(area: f64, gap: f64, er: f64) -> f64 {
+    EPSILON0 * er * area / gap
+}
+pub fn coax(length: f64, inner: f64, outer: f64, er: f64) -> (f64, f64) {
+    let x = (outer / inner).ln();
+    (
+        MU0 * length / (2f64 * f64::consts::PI) * x,
+        2f64 * f64::consts::PI * EPSILON0 * er * length / x,
+    )
+}
+
+// the line Elect prints with R: L of the wire alone or with the return wire at the spacing also C
+pub fn print_wire(length: f64, diameter: f64, spacing: Option<f64>, er: f64) {
+    match spacing {
+        Some(spacing) if spacing > diameter => {
+            let (inductance, capacitance) = two_wire(length, diameter, spacing, er);
+            println!(
+                "with the return wire at {} the loop has L = {} and C = {}",
+                format_value(spacing, "m"),
+                format_value(inductance, "H"),
+                format_value(capacitance, "F")
+            );
+        }
+        _ => println!(
+            "the straight wire has L = {}",
+            format_value(straight_wire(length, diameter), "H")
+        ),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Shape {
+    Wire,
+    Loop,
+    TwoWire,
+    Solenoid,
+    Plates,
+    Coax,
+}
+
+#[derive(Clone, Debug)]
+pub struct Geometry {
+    shape: Option<Shape>,
+    length: Option<f64>,   //m
+    diameter: Option<f64>, //m of the wire, the inner conductor or the coil
+    outer: Option<f64>,    //m of the loop or the shield
+    spacing: Option<f64>,  //m between the wires or the plates
+    area: Option<f64>,     //m² of the plates
+    turns: Option<f64>,
+    er: f64,
+    pub inductance: Option<f64>,  //H
+    pub capacitance: Option<f64>, //F
+}
+impl Geometry {
+    pub fn new(s: Vec<String>) -> Result<Geometry, &'static str> {
+        let mut geometry = Geometry {
+            shape: None,
+            length: None,
+            diameter: None,
+            outer: None,
+            spacing: None,
+            area: None,
+            turns: None,
+            er: 1f64,
+            inductance: None,
+            capacitance: None,
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "l" | "length" => geometry.length = Some(parse_value(&value, &["m"])?),
+                "d" | "di" => geometry.diameter = Some(parse_value(&value, &["m"])?),
+                "da" | "loop" => geometry.outer = Some(parse_value(&value, &["m"])?),
+                "s" | "spacing" | "gap" => geometry.spacing = Some(parse_value(&value, &["m"])?),
+                // the area in mm² like Elect
+                "a" | "area" => geometry.area = Some(parse_value(&value, &["mm2", "mm²"])? * 1e-6f64),
+                "n" | "turns" => geometry.turns = Some(parse_value(&value, &[])?),
+                "er" | "εr" => geometry.er = parse_value(&value, &[])?,
+                "" => {
+                    geometry.shape = Some(match value.to_lowercase().as_str() {
+                        "wire" => Shape::Wire,
+                        "loop" => Shape::Loop,
+                        "two-wire" | "twisted-pair" | "pair" => Shape::TwoWire,
+                        "solenoid" | "coil" => Shape::Solenoid,
+                        "plates" | "plate" => Shape::Plates,
+                        "coax" | "koax" => Shape::Coax,
+                        _ => return Err("shape not known"),
+                    })
+                }
+                _ => return Err("input is strange"),
+            }
+        }
+        if geometry.er < 1f64 {
+            return Err("the relative permittivity has to be 1 or more");
+        }
+        Ok(geometry)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        let positive = |value: Option<f64>, error: &'static str| match value {
+            Some(value) if value > 0f64 => Ok(value),
+            _ => Err(error),
+        };
+        let shape = match self.shape {
+            Some(shape) => shape,
+            None => return Err("give me wire, loop, two-wire, solenoid, plates or coax"),
+        };
+        // the wire can be given by its area like in Elect
+        let wire = self.diameter.or(self.area.map(|area| (4f64 * area / f64::consts::PI).sqrt()));
+        match shape {
+            Shape::Wire => {
+                let length = positive(self.length, "give me the length l")?;
+                let diameter = positive(wire, "give me the wire diameter d or area a")?;
+                self.inductance = Some(straight_wire(length, diameter));
+            }
+            Shape::Loop => {
+                let diameter = positive(wire, "give me the wire diameter d or area a")?;
+                let outer = positive(self.outer, "give me the diameter of the loop")?;
+                if outer <= diameter {
+                    return Err("the loop has to be larger than the wire");
+                }
+                self.inductance = Some(wire_loop(outer, diameter));
+            }
+            Shape::TwoWire => {
+                let length = positive(self.length, "give me the length l")?;
+                let diameter = positive(wire, "give me the wire diameter d or area a")?;
+                let spacing = positive(self.spacing, "give me the spacing s")?;
+                if spacing <= diameter {
+                    return Err("the spacing has to be larger than the wire diameter");
+                }
+                let (inductance, capacitance) = two_wire(length, diameter, spacing, self.er);
+                self.inductance = Some(inductance);
+                self.capacitance = Some(capacitance);
+            }
+            Shape::Solenoid => {
+                let turns = positive(self.turns, "give me the turns n")?;
+                let diameter = positive(self.diameter, "give me the coil diameter d")?;
+                let length = positive(self.length, "give me the winding length l")?;
+                self.inductance = Some(solenoid(turns, diameter, length));
+            }
+            Shape::Plates => {
+                let area = positive(self.area, "give me the area a of the plates")?;
+                let gap = positive(self.spacing, "give me the gap s")?;
+                self.capacitance = Some(plates(area, gap, self.er));
+            }
+            Shape::Coax => {
+                let length = positive(self.length, "give me the length l")?;
+                let inner = positive(self.diameter, "give me the inner diameter di")?;
+                let outer = positive(self.outer, "give me the shield diameter da")?;
+                if outer <= inner {
+                    return Err("the shield has to be larger than the inner conductor");
+                }
+                let (inductance, capacitance) = coax(length, inner, outer, self.er);
+                self.inductance = Some(inductance);
+                self.capacitance = Some(capacitance);
+            }
+        }
+        if should_print {
+            if let Some(inductance) = self.inductance {
+                println!("L = {}", format_value(inductance, "H"));
+            }
+            if let Some(capacitance) = self.capacitance {
+                println!("C = {}", format_value(capacitance, "F"));
+            }
+            // Wheeler is good to 1 % for coils longer than 0.4 of their diameter
+            if let (Shape::Solenoid, Some(length), Some(diameter)) =
+                (shape, self.length, self.diameter)
+            {
+                if length < 0.4f64 * diameter {
+                    println!("note: Wheeler's formula is only good for coils longer than 0.4 D");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::calculated;
+
+    fn geometry(given: &[&str]) -> Geometry {
+        calculated(given, Geometry::new, Geometry::calc).unwrap()
+    }
+
+    #[test]
+    fn straight_wire_rosa() {
+        // 1 m of 1 mm wire has about 1.5 µH
+        let inductance = straight_wire(1f64, 1e-3f64);
+        assert!((inductance - 2e-7f64 * (4000f64.ln() - 0.75f64)).abs() < 1e-15);
+        assert!((inductance - 1.5088e-6f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn wire_by_area() {
+        let by_area = geometry(&["wire", "l=1m", "a=1.5mm2"]).inductance.unwrap();
+        let diameter = (4f64 * 1.5e-6f64 / f64::consts::PI).sqrt();
+        assert!((by_area - straight_wire(1f64, diameter)).abs() < 1e-15);
+        let by_diameter = geometry(&["wire", "l=1m", "d=1mm"]).inductance.unwrap();
+        assert!((by_diameter - straight_wire(1f64, 1e-3f64)).abs() < 1e-15);
+    }
+
+    #[test]
+    fn coax_and_plates() {
+        // RG-58: 0.9 mm in 2.95 mm with PE gives about 100 pF/m
+        let (inductance, capacitance) = coax(1f64, 0.9e-3f64, 2.95e-3f64, 2.25f64);
+        assert!((inductance - 2.374e-7f64).abs() < 1e-9);
+        assert!((capacitance - 1.0544e-10f64).abs() < 1e-13);
+        assert!((plates(1f64, 1f64, 1f64) - EPSILON0).abs() < 1e-24);
+    }
+
+    #[test]
+    fn solenoid_wheeler() {
+        // 1 inch diameter, 1 inch long, 10 turns: 100 / 58 µH
+        assert!((solenoid(10f64, INCH, INCH) - 100f64 / 58f64 * 1e-6f64).abs() < 1e-15);
+    }
+}
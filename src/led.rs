@@ -0,0 +1,261 @@
+// series resistors for leds and led arrays. the leds are taken as constant voltage drop, the rest
+// of the supply voltage is across the resistor and the resistor itself is calculated with URI
+use std::f64;
+
+use super::{parse_value, split_given, URI};
+use colour_code::Colour;
+use eseries::ESeries;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Arrangement {
+    Series,         // all leds in one string with one resistor
+    Parallel,       // every led with its own resistor
+    SeriesParallel, // strings of leds, every string with its own resistor
+}
+
+// typical forward voltages, the colour names are the same as in the colour code
+fn forward_voltage(colour: Colour) -> Result<f64, &'static str> {
+    match colour {
+        Colour::Red => Ok(2.0f64),
+        Colour::Orange => Ok(2.1f64),
+        Colour::Yellow => Ok(2.1f64),
+        Colour::Green => Ok(3.0f64),
+        Colour::Blue => Ok(3.2f64),
+        Colour::White => Ok(3.2f64),
+        Colour::Violet => Ok(3.4f64),
+        _ => Err("there is no led with this colour"),
+    }
+}
+
+// the usual power ratings of resistors in W
+static POWER_RATINGS: [f64; 8] = [0.125, 0.25, 0.5, 1.0, 2.0, 3.0, 5.0, 10.0];
+
+// one string of leds with its resistor
+#[derive(Clone, Debug)]
+struct LedString {
+    leds: u32,
+    count: u32,    // how many of these strings there are
+    resistor: f64, //ohm calculated
+    snapped: f64,  //ohm from the e-series
+}
+
+#[derive(Clone, Debug)]
+pub struct Led {
+    arrangement: Arrangement,
+    u_supply: Option<f64>,  //V
+    u_forward: Option<f64>, //V of one led
+    current: Option<f64>,   //A of one led
+    leds: u32,
+    per_string: Option<u32>,
+    series: ESeries,
+    strings: Vec<LedString>,
+}
+impl Led {
+    pub fn new(s: Vec<String>) -> Result<Led, &'static str> {
+        let mut led = Led {
+            arrangement: Arrangement::Series,
+            u_supply: None,
+            u_forward: None,
+            current: None,
+            leds: 1,
+            per_string: None,
+            series: ESeries::E24,
+            strings: Vec::new(),
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "u" | "ub" => led.u_supply = Some(parse_value(&value, &["V"])?),
+                "uf" => led.u_forward = Some(parse_value(&value, &["V"])?),
+                "i" | "if" => led.current = Some(parse_value(&value, &["A"])?),
+                "colour" | "color" | "farbe" => {
+                    led.u_forward = Some(forward_voltage(Colour::new(&value)?)?)
+                }
+                "n" => {
+                    led.leds = match value.parse::<u32>() {
+                        Ok(n) if n > 0 => n,
+                        _ => return Err("number of leds not parseable"),
+                    }
+                }
+                "s" => {
+                    led.per_string = match value.parse::<u32>() {
+                        Ok(s) if s > 0 => Some(s),
+                        _ => return Err("number of leds per string not parseable"),
+                    }
+                }
+                "series" => led.series = ESeries::new(&value)?,
+                "" => {
+                    led.arrangement = match value.to_lowercase().as_str() {
+                        "series" | "reihe" => Arrangement::Series,
+                        "parallel" => Arrangement::Parallel,
+                        "mixed" | "seriesparallel" | "gemischt" => Arrangement::SeriesParallel,
+                        _ => return Err("arrangement not known"),
+                    }
+                }
+                _ => return Err("input is strange"),
+            }
+        }
+        if led.u_supply.is_none() || led.u_forward.is_none() || led.current.is_none() {
+            return Err("give me the supply voltage, the forward voltage or colour and the current");
+        }
+        if led.per_string.is_some() {
+            led.arrangement = Arrangement::SeriesParallel;
+        }
+        Ok(led)
+    }
+    // the resistor for a string of leds with URI: R = (U - n·Uf) / I
+    fn string_resistor(&self, leds: u32) -> Result<f64, &'static str> {
+        let u_supply = self.u_supply.unwrap_or(f64::NAN);
+        let u_forward = self.u_forward.unwrap_or(f64::NAN);
+        let current = self.current.unwrap_or(f64::NAN);
+        let u_resistor = u_supply - leds as f64 * u_forward;
+        if u_resistor.is_nan() || u_resistor <= 0f64 {
+            return Err("the supply voltage is too low for the leds in one string");
+        }
+        let mut uri = URI::new(vec![
+            format!("{}V", u_resistor),
+            format!("{}A", current),
+        ])?;
+        uri.calc(false);
+        if let Some(r) = uri.r {
+            Ok(r)
+        } else {
+            Err("the resistor is not calculable")
+        }
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        let u_supply = self.u_supply.unwrap_or(f64::NAN);
+        let u_forward = self.u_forward.unwrap_or(f64::NAN);
+        // (leds per string, number of strings) for the longer and the shorter strings
+        let groups = match self.arrangement {
+            Arrangement::Series => [(self.leds, 1), (0, 0)],
+            Arrangement::Parallel => [(1, self.leds), (0, 0)],
+            Arrangement::SeriesParallel => {
+                if let Some(per_string) = self.per_string {
+                    // the given length for all strings, the rest of the leds in one more string
+                    [(per_string, self.leds / per_string), (self.leds % per_string, 1)]
+                } else {
+                    // as few strings as possible, but at least 1 V stays for the resistor. the
+                    // leds are spread evenly over the strings, so 7 leds become 3+2+2
+                    let max = ((u_supply - 1f64) / u_forward).floor();
+                    let max = if max >= 1f64 { (max as u32).min(self.leds) } else { 1 };
+                    let strings = self.leds.div_ceil(max);
+                    let longer = self.leds % strings;
+                    let per_string = self.leds / strings;
+                    [(per_string + 1, longer), (per_string, strings - longer)]
+                }
+            }
+        };
+        self.strings.clear();
+        for &(leds, count) in groups.iter() {
+            if leds > 0 && count > 0 {
+                let resistor = self.string_resistor(leds)?;
+                self.strings.push(LedString {
+                    leds,
+                    count,
+                    resistor,
+                    snapped: self.series.nearest(resistor),
+                });
+            }
+        }
+        if should_print {
+            self.print_res()
+        }
+        Ok(())
+    }
+    fn print_res(&self) {
+        let u_supply = self.u_supply.unwrap_or(0f64);
+        let u_forward = self.u_forward.unwrap_or(0f64);
+        let current = self.current.unwrap_or(0f64);
+        println!(
+            "{} leds with {} V and {} A at {} V:",
+            self.leds, u_forward, current, u_supply
+        );
+        let mut p_leds = 0f64;
+        let mut i_total = 0f64;
+        for string in self.strings.iter() {
+            let u_resistor = u_supply - string.leds as f64 * u_forward;
+            // the real current with the snapped resistor
+            let i_real = u_resistor / string.snapped;
+            let p_resistor = i_real * i_real * string.snapped;
+            let rating = POWER_RATINGS
+                .iter()
+                .find(|rating| **rating >= 2f64 * p_resistor)
+                .map_or("more than 10 W".to_string(), |rating| format!("{} W", rating));
+            println!(
+                "{} x string of {} leds: R = {} Ω, with {:?} R = {} Ω and I = {} A",
+                string.count, string.leds, string.resistor, self.series, string.snapped, i_real
+            );
+            println!(
+                "the power in the resistor is: {} W, use at least a {} resistor",
+                p_resistor, rating
+            );
+            // (ΔI/I) / (ΔU/U) = U / (U - n·Uf)
+            let sensitivity = u_supply / u_resistor;
+            println!(
+                "1 % more supply voltage gives {} % more current, +10 % gives {} A",
+                sensitivity,
+                (1.1f64 * u_supply - string.leds as f64 * u_forward) / string.snapped
+            );
+            p_leds += string.count as f64 * string.leds as f64 * u_forward * i_real;
+            i_total += string.count as f64 * i_real;
+        }
+        println!("The total current is: {} A", i_total);
+        if i_total > 0f64 {
+            println!(
+                "The efficiency is: {} % ({} W in the leds of {} W)",
+                p_leds / (u_supply * i_total) * 100f64,
+                p_leds,
+                u_supply * i_total
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::calculated;
+
+    fn led(given: &[&str]) -> Result<Led, &'static str> {
+        calculated(given, Led::new, Led::calc)
+    }
+
+    #[test]
+    fn single_led() {
+        let led = led(&["u=5V", "colour=red", "i=20mA"]).unwrap();
+        assert_eq!(led.strings.len(), 1);
+        assert!((led.strings[0].resistor - 150f64).abs() < 1e-9);
+        assert_eq!(led.strings[0].snapped, 150f64);
+    }
+
+    #[test]
+    fn strings_spread_evenly() {
+        // three leds at most fit into 12 V, so 7 leds need three strings: one of three and two
+        // of two leds
+        let led = led(&["u=12V", "uf=3V", "i=20mA", "n=7", "mixed"]).unwrap();
+        assert_eq!(led.strings.len(), 2);
+        assert_eq!((led.strings[0].leds, led.strings[0].count), (3, 1));
+        assert!((led.strings[0].resistor - 150f64).abs() < 1e-9);
+        assert_eq!((led.strings[1].leds, led.strings[1].count), (2, 2));
+        assert!((led.strings[1].resistor - 300f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn strings_with_the_rest() {
+        // two strings of the given three leds, the seventh led gets its own string
+        let led = led(&["u=12V", "uf=3V", "i=20mA", "n=7", "s=3"]).unwrap();
+        assert_eq!(led.strings.len(), 2);
+        assert_eq!((led.strings[0].leds, led.strings[0].count), (3, 2));
+        assert!((led.strings[0].resistor - 150f64).abs() < 1e-9);
+        assert_eq!((led.strings[1].leds, led.strings[1].count), (1, 1));
+        assert!((led.strings[1].resistor - 450f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn strange_leds() {
+        assert!(led(&["u=5V", "colour=red", "i=20mA", "n=3"]).is_err());
+        assert!(led(&["u=5V", "colour=black", "i=20mA"]).is_err());
+        assert!(led(&["u=5V", "i=20mA"]).is_err());
+    }
+}
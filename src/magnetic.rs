@@ -0,0 +1,279 @@
+// magnetic circuits work like URI: the magnetomotive force drives the flux through the reluctance
+//   Θ = Φ * Rm    with Θ = N * I in A (ampere-turns), Φ in Wb and Rm in 1/H
+// two of them are given and the third is calculated. the reluctance comes from the geometry of the
+// core and its air gaps in series:
+//   Rm = le / (µ0 * µr * Ae) + g / (µ0 * Ag)
+// the gap is taken with its fringing flux, the field spreads out by the gap length on every side of
+// a square core leg: Ag = (sqrt(Ae) + g)². with the turns the inductance is L = N² / Rm
+use std::f64;
+
+use super::{format_value, parse_value, split_given};
+
+const MU0: f64 = 4e-7f64 * f64::consts::PI;
+
+// the relative permeability and the saturation flux density in T of the core materials
+fn core_material(material: &str) -> Option<(f64, f64)> {
+    match material {
+        "steel" | "silicon-steel" | "electrical-steel" | "trafoblech" => Some((4000f64, 1.5f64)),
+        "iron" | "eisen" => Some((5000f64, 2f64)),
+        "amorphous" => Some((10000f64, 1.5f64)),
+        "nanocrystalline" => Some((30000f64, 1.2f64)),
+        "powder" | "iron-powder" => Some((75f64, 1f64)),
+        "ferrite" => Some((2000f64, 0.35f64)),
+        "air" => Some((1f64, f64::INFINITY)),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MagneticCircuit {
+    path: Option<f64>,       //m mean magnetic path length le
+    area: Option<f64>,       //m² cross section Ae
+    mu_r: Option<f64>,
+    saturation: Option<f64>, //T of the material
+    gap: f64,                //m of all air gaps in the path
+    turns: Option<f64>,
+    current: Option<f64>,  //A
+    flux_density: Option<f64>, //T in the core
+    pub mmf: Option<f64>,        //A
+    pub flux: Option<f64>,       //Wb
+    pub reluctance: Option<f64>, //1/H
+}
+impl MagneticCircuit {
+    pub fn new(s: Vec<String>) -> Result<MagneticCircuit, &'static str> {
+        let mut circuit = MagneticCircuit {
+            path: None,
+            area: None,
+            mu_r: None,
+            saturation: None,
+            gap: 0f64,
+            turns: None,
+            current: None,
+            flux_density: None,
+            mmf: None,
+            flux: None,
+            reluctance: None,
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "le" | "path" => circuit.path = Some(parse_value(&value, &["m"])?),
+                "ae" | "ac" | "area" => {
+                    circuit.area = Some(parse_value(&value, &["mm2", "mm²"])? * 1e-6f64)
+                }
+                "mur" | "µr" | "μr" | "ur" => circuit.mu_r = Some(parse_value(&value, &[])?),
+                // every gap in the path adds its length, like the two legs of an e core
+                "g" | "gap" => circuit.gap += parse_value(&value, &["m"])?,
+                "n" | "turns" => circuit.turns = Some(parse_value(&value, &[])?),
+                "i" | "current" => circuit.current = Some(parse_value(&value, &["A"])?),
+                "theta" | "θ" | "mmf" => circuit.mmf = Some(parse_value(&value, &["At", "A"])?),
+                "phi" | "φ" | "flux" => circuit.flux = Some(parse_value(&value, &["Wb"])?),
+                "b" => circuit.flux_density = Some(parse_value(&value, &["T"])?),
+                "rm" | "reluctance" => {
+                    circuit.reluctance = Some(parse_value(&value, &["1/H", "A/Wb"])?)
+                }
+                "" | "material" | "mat" => match core_material(&value.to_lowercase()) {
+                    Some((mu_r, saturation)) => {
+                        if circuit.mu_r.is_none() {
+                            circuit.mu_r = Some(mu_r);
+                        }
+                        circuit.saturation = Some(saturation);
+                    }
+                    None => return Err("core material not known"),
+                },
+                _ => return Err("input is strange"),
+            }
+        }
+        // Θ = N * I
+        match (circuit.turns, circuit.current, circuit.mmf) {
+            (Some(_), Some(_), Some(_)) => return Err("give me either N and I or Θ"),
+            (Some(turns), Some(current), None) => circuit.mmf = Some(turns * current),
+            (None, Some(_), _) => return Err("give me the turns n for the current"),
+            _ => {}
+        }
+        // the reluctance of the geometry
+        match (circuit.path, circuit.area) {
+            (Some(path), Some(area)) if path > 0f64 && area > 0f64 => {
+                if circuit.reluctance.is_some() {
+                    return Err("give me either the geometry or the reluctance");
+                }
+                let mu_r = match circuit.mu_r {
+                    Some(mu_r) if mu_r >= 1f64 => mu_r,
+                    _ => return Err("give me the core material or µr of 1 or more"),
+                };
+                let gap_area = (area.sqrt() + circuit.gap).powi(2);
+                circuit.reluctance =
+                    Some(path / (MU0 * mu_r * area) + circuit.gap / (MU0 * gap_area));
+            }
+            // the cross section alone is only for the flux density
+            (None, _) => {}
+            _ => return Err("give me the path length le and the cross section ae of the core"),
+        }
+        // Φ = B * Ae
+        if let Some(flux_density) = circuit.flux_density {
+            match (circuit.area, circuit.flux) {
+                (Some(area), None) => circuit.flux = Some(flux_density * area),
+                (None, _) => return Err("give me the cross section ae for the flux density"),
+                (Some(_), Some(_)) => return Err("give me either the flux or the flux density"),
+            }
+        }
+        let given = [circuit.mmf, circuit.flux, circuit.reluctance];
+        if given.iter().all(|value| value.is_some()) {
+            return Err("give me only two of Θ, Φ and Rm");
+        }
+        if given.iter().any(|value| value.is_some_and(|value| value <= 0f64)) {
+            return Err("Θ, Φ and Rm have to be positive");
+        }
+        Ok(circuit)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        // the one of Θ, Φ and Rm which isn't given, like calc of URI
+        let num = match (self.mmf, self.flux, self.reluctance) {
+            (None, Some(flux), Some(reluctance)) => {
+                self.mmf = Some(flux * reluctance);
+                0
+            }
+            (Some(mmf), None, Some(reluctance)) => {
+                self.flux = Some(mmf / reluctance);
+                1
+            }
+            (Some(mmf), Some(flux), None) => {
+                self.reluctance = Some(mmf / flux);
+                2
+            }
+            // only the reluctance, for the inductance
+            (None, None, Some(_)) if self.turns.is_some() => -1,
+            _ => return Err("give me two of Θ (or N and I), Φ (or B) and Rm (or the core)"),
+        };
+        // the current of the ampere-turns
+        if let (Some(turns), Some(mmf), None) = (self.turns, self.mmf, self.current) {
+            self.current = Some(mmf / turns);
+        }
+        if should_print {
+            self.print_res(num)
+        }
+        Ok(())
+    }
+    fn print_res(&self, num: i8) {
+        let mmf = self.mmf.unwrap_or(0f64);
+        let flux = self.flux.unwrap_or(0f64);
+        let reluctance = self.reluctance.unwrap_or(0f64);
+        match num {
+            0 => println!(
+                "the ampere-turns for the flux of {} and the reluctance of {} are: Θ = {}",
+                format_value(flux, "Wb"),
+                format_value(reluctance, "A/Wb"),
+                format_value(mmf, "A")
+            ),
+            1 => println!(
+                "the flux for Θ = {} and the reluctance of {} is: Φ = {}",
+                format_value(mmf, "A"),
+                format_value(reluctance, "A/Wb"),
+                format_value(flux, "Wb")
+            ),
+            2 => println!(
+                "the reluctance for Θ = {} and the flux of {} is: Rm = {}",
+                format_value(mmf, "A"),
+                format_value(flux, "Wb"),
+                format_value(reluctance, "A/Wb")
+            ),
+            _ => println!("the reluctance is: Rm = {}", format_value(reluctance, "A/Wb")),
+        }
+        // the parts of the geometry in series
+        if let (Some(path), Some(area), Some(mu_r)) = (self.path, self.area, self.mu_r) {
+            if self.gap > 0f64 {
+                let core = path / (MU0 * mu_r * area);
+                println!(
+                    "the core has {} and the gap of {} {}, the effective µr is {:.1}",
+                    format_value(core, "A/Wb"),
+                    format_value(self.gap, "m"),
+                    format_value(reluctance - core, "A/Wb"),
+                    path / (MU0 * area * reluctance)
+                );
+            }
+        }
+        if let Some(turns) = self.turns {
+            println!(
+                "with {} turns: L = {}, AL = {}/N²",
+                turns,
+                format_value(turns * turns / reluctance, "H"),
+                format_value(1f64 / reluctance, "H")
+            );
+            // the inverse, the current for the needed ampere-turns
+            if let Some(current) = self.current.filter(|_| num == 0) {
+                println!("the current for Θ is: I = {}", format_value(current, "A"));
+            }
+        }
+        if let Some(area) = self.area.filter(|_| num >= 0) {
+            let flux_density = flux / area;
+            print!("the flux density in the core is: B = {}", format_value(flux_density, "T"));
+            match self.mu_r {
+                Some(mu_r) => println!(
+                    ", the field strength H = {}/m",
+                    format_value(flux_density / (MU0 * mu_r), "A")
+                ),
+                None => println!(),
+            }
+            if let Some(saturation) = self.saturation {
+                if flux_density > saturation {
+                    println!(
+                        "note: the core saturates at {}, the flux is smaller",
+                        format_value(saturation, "T")
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::calculated;
+
+    fn circuit(given: &[&str]) -> Result<MagneticCircuit, &'static str> {
+        calculated(given, MagneticCircuit::new, MagneticCircuit::calc)
+    }
+
+    #[test]
+    fn reluctance() {
+        let core = 0.1f64 / (MU0 * 2000f64 * 1e-4f64);
+        let ungapped = circuit(&["ferrite", "le=0.1m", "ae=100mm2", "n=10"]).unwrap();
+        assert!((ungapped.reluctance.unwrap() - core).abs() / core < 1e-12);
+        // the gap of 1 mm fringes out to 11 mm x 11 mm
+        let gapped = circuit(&["ferrite", "le=0.1m", "ae=100mm2", "g=1mm", "n=10"]).unwrap();
+        let gap = 1e-3f64 / (MU0 * 11e-3f64 * 11e-3f64);
+        assert!((gapped.reluctance.unwrap() - core - gap).abs() / gap < 1e-12);
+        // two gaps add up
+        let twice = circuit(&["ferrite", "le=0.1m", "ae=100mm2", "g=0.5mm", "g=0.5mm", "n=1"]);
+        assert_eq!(twice.unwrap().reluctance, gapped.reluctance);
+        let given = circuit(&["ferrite", "le=0.1m", "ae=100mm2", "mur=100", "n=1"]).unwrap();
+        assert!((given.reluctance.unwrap() - core * 20f64).abs() / core < 1e-12);
+    }
+
+    #[test]
+    fn mmf_flux_and_current() {
+        let flux = circuit(&["n=100", "i=2A", "rm=1e6"]).unwrap();
+        assert_eq!(flux.mmf, Some(200f64));
+        assert!((flux.flux.unwrap() - 2e-4f64).abs() < 1e-15);
+        let mmf = circuit(&["n=100", "phi=2e-4Wb", "rm=1e6"]).unwrap();
+        assert!((mmf.mmf.unwrap() - 200f64).abs() < 1e-9);
+        assert!((mmf.current.unwrap() - 2f64).abs() < 1e-12);
+        let reluctance = circuit(&["θ=200A", "b=0.5T", "ae=400mm2"]).unwrap();
+        assert!((reluctance.reluctance.unwrap() - 1e6f64).abs() < 1e-3);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(circuit(&["i=1A", "rm=1e6"]).is_err());
+        assert!(circuit(&["n=10", "i=1A", "θ=10A", "rm=1e6"]).is_err());
+        assert!(circuit(&["le=0.1m", "ae=100mm2", "n=10"]).is_err());
+        assert!(circuit(&["ferrite", "le=0.1m", "n=10"]).is_err());
+        assert!(circuit(&["ferrite", "le=0.1m", "ae=100mm2", "rm=1e6", "θ=1A"]).is_err());
+        assert!(circuit(&["θ=1A", "phi=1Wb", "rm=1e6"]).is_err());
+        assert!(circuit(&["θ=-1A", "rm=1e6"]).is_err());
+        assert!(circuit(&["b=1T", "rm=1e6", "θ=1A"]).is_err());
+        assert!(circuit(&["θ=1A"]).is_err());
+        assert!(circuit(&["unobtainium", "le=0.1m", "ae=100mm2"]).is_err());
+    }
+}
@@ -1,20 +1,52 @@
 #[macro_use]
 extern crate lazy_static; //for reducing generating expensive objects more than once
-#[macro_use]
-extern crate text_io; // todo: remove code
 extern crate regex; // for the regular expressions used to convert the user input
+extern crate rustyline; // line editing, history and completion for the interactive mode
 
 use regex::Regex; // namespace for not typing regex::Regex every time again
 use std::f64; //a namespace too
-use std::io; //a namespace for the io functions
-#[lang = "char"]
-impl char{
+use std::env; // for the arguments of the command line
+use std::process; // for the exit code
+
+mod three_phase; // star/delta, line and phase values, power and voltage drop of three phase systems
+mod transient; // time constants, charge/discharge curves and the RLC step response
+mod eseries; // the preferred values E6 to E192, combinations and dividers
+mod filter; // design of passive RC, LC and RLC filters
+mod colour_code; // resistor colour codes and smd markings in both directions
+mod passive; // capacitor and inductor markings, combinations and stored energy
+mod divider; // design of loaded voltage dividers
+mod led; // series resistors for leds and led arrays
+mod tolerance; // tolerances of the given values through Elect and URI
+mod monte_carlo; // monte carlo analysis of Elect and URI with a seedable random generator
+mod sweep; // parameter sweeps over Elect and URI as table, csv or json
+mod plot; // ascii and svg plots of sweeps
+mod cli; // the subcommands of the command line
+mod repl; // the interactive mode with variables, results and sessions
+mod quantity; // values with their dimension in the si base units
+mod expression; // arithmetic with units like 2*15m + 3m
+mod convert; // conversion of values between units, also AWG and dBm
+mod decibel; // gains, losses and levels in dB and chains of them
+mod resistivity; // the forms of the specific resistance, conductivity and sheet resistance
+mod pcb; // traces and vias on printed circuit boards
+mod transmission_line; // impedance, delay and loss of coax, twisted pair, microstrip and stripline
+mod inductance; // inductance and capacitance of wires, loops, coils, plates and coax
+mod coil; // coils and windings on a bobbin
+mod transformer; // ratio, currents, losses and the core of transformers
+mod magnetic; // magnetic circuits with reluctance, flux and ampere-turns
+
+trait AsciiFloat {
+    fn is_ascii_float(&self) -> bool;
+}
+impl AsciiFloat for char{
     fn is_ascii_float(&self) -> bool {
         let possible_chars: Vec<char> = vec!['0','1','2','3','4','5','6','7','8','9','e','-','.'];
-        possible_chars.contains(&self)
+        possible_chars.contains(self)
     }
 }
 
+// the lowercase variants are written like the si prefixes
+#[allow(non_camel_case_types)]
+#[derive(Clone, Debug)]
 enum PreUnits {
     P,
     T,
@@ -27,6 +59,7 @@ enum PreUnits {
     m,
     my,
     n,
+    p,
 }
 impl PreUnits{
     fn return_pow(&self) -> i16 {
@@ -41,20 +74,33 @@ impl PreUnits{
             PreUnits::z => -2,
             PreUnits::m => -3,
             PreUnits::my => -6,
-            PreUnits::n => -12,
+            PreUnits::n => -9,
+            PreUnits::p => -12,
         }
     }
     fn convert_pre_unit(&self, num: f64, goal_pre_unit: PreUnits) -> (f64, PreUnits) {
         let mut pow: i16 = self.return_pow();
-        pow = pow - goal_pre_units.return_pow();
-        if pow != 0 {
-            (num / pow as f64, goal_pre_unit)
+        pow -= goal_pre_unit.return_pow();
+        if pow < 0 {
+            (num / 10f64.powi(-pow as i32), goal_pre_unit)
+        } else if pow > 0 {
+            (num * 10f64.powi(pow as i32), goal_pre_unit)
         } else {
             (num, goal_pre_unit)
         }
     }
+    // scales a number with this pre unit to the base unit. dividing for the small pre units keeps
+    // values like 4.7n free of rounding noise
+    fn to_base(&self, num: f64) -> f64 {
+        let pow = self.return_pow() as i32;
+        if pow < 0 {
+            num / 10f64.powi(-pow)
+        } else {
+            num * 10f64.powi(pow)
+        }
+    }
     fn new(pre_unit: String) -> PreUnits {
-        match pre_unit {
+        match pre_unit.as_str() {
             "P" => PreUnits::P,
             "T" => PreUnits::T,
             "G" => PreUnits::G,
@@ -64,14 +110,142 @@ impl PreUnits{
             "d" => PreUnits::d,
             "z" => PreUnits::z,
             "m" => PreUnits::m,
-            "µ" | "my" => PreUnits::my,
+            "µ" | "u" | "my" => PreUnits::my,
             "n" => PreUnits::n,
+            "p" => PreUnits::p,
             _   => PreUnits::No,
         }
     }
+    fn symbol(&self) -> &'static str {
+        match self {
+            PreUnits::P => "P",
+            PreUnits::T => "T",
+            PreUnits::G => "G",
+            PreUnits::M => "M",
+            PreUnits::k => "k",
+            PreUnits::No => "",
+            PreUnits::d => "d",
+            PreUnits::z => "z",
+            PreUnits::m => "m",
+            PreUnits::my => "µ",
+            PreUnits::n => "n",
+            PreUnits::p => "p",
+        }
+    }
+    // the pre unit which gives a number between 1 and 1000 for the value. deci and centi are left
+    // out, nobody writes 4.7 dV
+    fn for_value(num: f64) -> PreUnits {
+        let pow = if num != 0f64 && num.is_finite() {
+            (num.abs().log10() / 3f64).floor() as i32 * 3
+        } else {
+            0
+        };
+        match pow {
+            _ if pow >= 15 => PreUnits::P,
+            12 => PreUnits::T,
+            9 => PreUnits::G,
+            6 => PreUnits::M,
+            3 => PreUnits::k,
+            0 => PreUnits::No,
+            -3 => PreUnits::m,
+            -6 => PreUnits::my,
+            -9 => PreUnits::n,
+            _ => PreUnits::p,
+        }
+    }
+}
+
+// a more general form of the parsing in Voltage::new and Current::new. the input is split in the
+// number, the pre unit and the unit. the number is scaled with the pre unit so every module can
+// work with the base units (V, A, ohm, W, ...). units is a list of allowed spellings for the unit.
+// an expression like 2*15m + 3m is calculated and given in the first unit of the list
+fn parse_value(input: &str, units: &[&str]) -> Result<f64, &'static str> {
+    match parse_literal(input, units) {
+        Err(_) if expression::is_expression(input) => {
+            expression::evaluate_in(input, units.first().cloned().unwrap_or(""))
+        }
+        result => result,
+    }
+}
+fn parse_literal(input: &str, units: &[&str]) -> Result<f64, &'static str> {
+    let input = input.trim();
+    let split_point = input.find(|c: char| !c.is_ascii_float()).unwrap_or(input.len());
+    let (num_str, rest) = input.split_at(split_point);
+    let num = if let Ok(num) = num_str.parse::<f64>() {
+        num
+    } else {
+        return Err("number not parseable");
+    };
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return Ok(num);
+    }
+    // the rkm code of IEC 60062 like 4k7 or 2R2: the pre unit (or R for none) takes the place of
+    // the decimal point
+    let mut chars = rest.chars();
+    if let Some(pre_unit) = chars.next() {
+        let after: String = chars.collect();
+        let digits_end = after.find(|c: char| !c.is_ascii_digit()).unwrap_or(after.len());
+        let unit = &after[digits_end..];
+        if digits_end > 0
+            && !num_str.contains('.')
+            && "PTGMkmuµnpR".contains(pre_unit)
+            && (unit.is_empty() || units.contains(&unit))
+        {
+            if let Ok(num) = format!("{}.{}", num_str, &after[..digits_end]).parse::<f64>() {
+                let pre_unit = if pre_unit == 'R' { "".to_string() } else { pre_unit.to_string() };
+                return Ok(PreUnits::new(pre_unit).to_base(num));
+            }
+        }
+    }
+    for unit in units.iter() {
+        if let Some(pre_unit) = rest.strip_suffix(unit) {
+            // PreUnits::new falls back to no pre unit, so we check the known ones here first
+            match pre_unit {
+                "P" | "T" | "G" | "M" | "k" | "" | "d" | "z" | "m" | "µ" | "u" | "my" | "n"
+                | "p" => {
+                    return Ok(PreUnits::new(pre_unit.to_string()).to_base(num));
+                }
+                _ => {}
+            }
+        }
+    }
+    Err("unit not known")
+}
+// the other way round of parse_value: a value in the base unit is written with the fitting pre
+// unit and four significant digits, like 4.7 kΩ
+fn format_value(num: f64, unit: &str) -> String {
+    // there is no pre unit for values like 2^1000, the number would have hundreds of digits
+    if num != 0f64 && (num.abs() >= 1e18f64 || num.abs() < 1e-15f64) {
+        return format!("{:.3e} {}", num, unit);
+    }
+    let (scaled, pre_unit) = PreUnits::No.convert_pre_unit(num, PreUnits::for_value(num));
+    let digits = if scaled != 0f64 {
+        (3 - scaled.abs().log10().floor() as i32).max(0) as usize
+    } else {
+        0
+    };
+    let mut number = format!("{:.*}", digits, scaled);
+    if number.contains('.') {
+        number = number.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+    format!("{} {}{}", number, pre_unit.symbol(), unit)
+}
+// splits a given value like "ul=400V" or "ul:400V" in the lowercase name and the value. if there
+// is no name the name is empty and the whole input is the value
+fn split_given(input: &str) -> (String, String) {
+    if let Some(split_point) = input.find(['=', ':']) {
+        (
+            input[..split_point].trim().to_lowercase(),
+            input[split_point + 1..].trim().to_string(),
+        )
+    } else {
+        ("".to_string(), input.trim().to_string())
+    }
 }
 
 // an enum for the different materials
+#[derive(Clone, Copy)]
 enum RhoMaterial {
     Copper,
     Iron,
@@ -100,6 +274,25 @@ fn select_rho(rho: RhoMaterial) -> f64 {
         RhoMaterial::Tungsten => 6.03e-2f64,
     }
 }
+// the names of the materials Elect knows, in english and german
+static MATERIAL_NAMES: [&str; 16] = [
+    "copper", "kupfer", "iron", "eisen", "aluminium", "alu", "gold", "graphit", "silver", "silber",
+    "platinum", "platin", "lead", "blei", "tungsten", "nickel",
+];
+// the temperature coefficients at 20 °C in 1/K, so rho can be given for other temperatures
+fn select_alpha(rho: RhoMaterial) -> f64 {
+    match rho {
+        RhoMaterial::Copper => 3.93e-3f64,
+        RhoMaterial::Iron => 5.6e-3f64,
+        RhoMaterial::Aluminium => 4.03e-3f64,
+        RhoMaterial::Gold => 3.7e-3f64,
+        RhoMaterial::Graphit => -5e-4f64,
+        RhoMaterial::Silver => 3.8e-3f64,
+        RhoMaterial::Platinum => 3.92e-3f64,
+        RhoMaterial::Lead => 4.2e-3f64,
+        RhoMaterial::Tungsten => 4.4e-3f64,
+    }
+}
 #[derive(Clone,Debug)]
 struct Voltage{
     value: f64,
@@ -121,6 +314,10 @@ impl Voltage{
             Err("number not parseable")
         }
     }
+    // the voltage in V without the pre unit
+    fn base_value(&self) -> f64 {
+        self.pre_unit.to_base(self.value)
+    }
 }
 
 #[derive(Clone,Debug)]
@@ -144,7 +341,10 @@ impl Current{
             Err("number not parseable")
         }
     }
-
+    // the current in A without the pre unit
+    fn base_value(&self) -> f64 {
+        self.pre_unit.to_base(self.value)
+    }
 }
 // derive is a macro for the default implementation of traits. traits are feature class without
 // specifing any data beside of types that have to be present to complete the task. they don't have
@@ -171,17 +371,13 @@ impl Area {
         // for the mutable variable we have to use let mut for a defenition and for the self a
         // mutable borrow of the object &mut self
         // let pid2 = |d| 3.141f64 * d * d;
-        self.a = if let Some(d) = self.d.clone() {
-            Some(3.141f64 * (d * d) / 4f64)
-        } else {
-            None
-        }
+        self.a = self.d.map(|d| f64::consts::PI * (d * d) / 4f64)
     }
     fn calc_d(&mut self) {
         // here we can see how the type of a variable is defined in rust. unlike in c and others,
         // rust again uses the ML-annotion with variablename: type
-        let d: f64 = if let Some(a) = self.a.clone() {
-            (a * 4f64) / 3.141f64
+        let d: f64 = if let Some(a) = self.a {
+            (a * 4f64) / f64::consts::PI
         } else {
             -1f64
         };
@@ -195,23 +391,61 @@ struct Elect {
     rho: Option<f64>, //ohm*mm2/m
     length: Option<f64>, //m
     area: Option<Area>, //d:mm A:mm2
+    temperature: Option<f64>, //°C
+    alpha: Option<f64>, //1/K of the material
+    thickness: Option<f64>, //mm of a film or the pcb copper, for the sheet resistance
+    spacing: Option<f64>, //m to the return wire, for the inductance and capacitance
+    er: Option<f64>, //relative permittivity of the insulation between the wires
 }
 impl Elect {
     // here is somewhat of a kind of constructor known from c++ and java.
     // also we are using somekind of errorhandling which is more like what is used in c
     fn new(s: Vec<String>) -> Result<Elect, &'static str> {
         let mut elect: Elect = Default::default();
+        let mut sheet_resistance: Option<f64> = None; //ohm
         for i in s.iter() {
             // lazy_static! is a macro which helps to reduce the defenition of expensive
             // decleration in loops. 
             // with the following regex we try to capture the values the string vec is giving us 
             lazy_static!  {
-                static ref RENUM: Regex = Regex::new(r"([0-9\.]+)(ohmxmm2/m|ohm|mm2|mm|m)").unwrap();
-                static ref REMAT: Regex = Regex::new(r"([[:alpha:]]+)").unwrap();
+                static ref RENUM: Regex =
+                    Regex::new(r"^(-?[0-9\.]+(?:e-?[0-9]+)?)(ohmxmm2/m|ohm|mm2|mm|m)$").unwrap();
+                static ref REMAT: Regex = Regex::new(r"^([[:alpha:]]+)$").unwrap();
+                static ref RETEMP: Regex = Regex::new(r"^(-?[0-9\.]+) ?(°C|degC)$").unwrap();
+            }
+            // the temperature is checked first, otherwise the C would be taken as material
+            if let Some(capstemp) = RETEMP.captures(i.trim()) {
+                elect.temperature = capstemp.get(1).and_then(|m| m.as_str().parse::<f64>().ok());
+                continue;
+            }
+            // the thickness and the spacing are named, they would be taken as diameter or length
+            // otherwise
+            let (name, value) = split_given(i);
+            if name == "thickness" || name == "h" {
+                elect.thickness = Some(resistivity::parse_thickness(&value)?);
+                continue;
+            }
+            if name == "spacing" || name == "s" {
+                elect.spacing = Some(parse_value(&value, &["m"])?);
+                continue;
+            }
+            if name == "er" || name == "εr" {
+                elect.er = Some(parse_value(&value, &[])?);
+                continue;
+            }
+            // the sheet resistance and the other forms of rho like 58MS/m or 1.72µΩ·cm. the sheet
+            // resistance needs the thickness, so it is kept until all values are there
+            if let Some(sheet) = resistivity::parse_sheet(i) {
+                sheet_resistance = Some(sheet?);
+                continue;
+            }
+            if let Some(rho) = resistivity::parse_rho(i) {
+                elect.rho = Some(rho?);
+                continue;
             }
             // here happens the actual capture
-            let capsnum = RENUM.captures(&i);
-            let capsmat = REMAT.captures(&i);
+            let capsnum = RENUM.captures(i.trim());
+            let capsmat = REMAT.captures(i.trim());
             // some non initialized unmutable variable where we can assign a value once
             let num: String;
             let einheit: String;
@@ -237,30 +471,22 @@ impl Elect {
             let einheit = einheit.as_str();
             let material = material.as_str();
             // to check if the first regex has matched
-            if num != "" && einheit != "" {
+            if !num.is_empty() && !einheit.is_empty() {
+                // the sign is read too, otherwise -10m would be a wire of 10 m
+                if num.starts_with('-') {
+                    return Err("the values of the wire can't be negative");
+                }
                 // then we match the str einheiten against there possible ones to assign the value
                 // the right field in the struct
                 match einheit {
                     "ohm" => {
-                        elect.resistor = if let Ok(i) = num.parse::<f64>() {
-                            Some(i)
-                        } else {
-                            None
-                        };
+                        elect.resistor = num.parse::<f64>().ok();
                     }
                     "ohmxmm2/m" => {
-                        elect.rho = if let Ok(i) = num.parse::<f64>() {
-                            Some(i)
-                        } else {
-                            None
-                        }
+                        elect.rho = num.parse::<f64>().ok()
                     }
                     "m" => {
-                        elect.length = if let Ok(i) = num.parse::<f64>() {
-                            Some(i)
-                        } else {
-                            None
-                        }
+                        elect.length = num.parse::<f64>().ok()
                     }
                     "mm" => {
                         elect.area = if let Ok(i) = num.parse::<f64>() {
@@ -292,7 +518,7 @@ impl Elect {
                     }
                     _ => return Err("Some wrong input"),
                 }
-            } else if num == "" && einheit == "" && material != "" {
+            } else if num.is_empty() && einheit.is_empty() && !material.is_empty() {
                 let mat = match material {
                     "iron" | "eisen" => RhoMaterial::Iron,
                     "copper" | "kupfer" => RhoMaterial::Copper,
@@ -305,7 +531,31 @@ impl Elect {
                     "tungsten" | "nickel" => RhoMaterial::Tungsten,
                     _ => return Err("material not found"),
                 };
+                elect.alpha = Some(select_alpha(mat));
                 elect.rho = Some(select_rho(mat));
+            } else {
+                // something like 8ohm in 1.72e-8ohmxm, the rest would be lost without a word
+                return Err("input is strange");
+            }
+        }
+        // rho = Rs * t, with t in mm and rho in ohm*mm2/m
+        if let Some(sheet_resistance) = sheet_resistance {
+            if elect.rho.is_some() {
+                return Err("give me either the material, rho or the sheet resistance");
+            }
+            match elect.thickness {
+                Some(thickness) => elect.rho = Some(sheet_resistance * thickness * 1000f64),
+                None => return Err("give me the thickness for the sheet resistance"),
+            }
+        }
+        // the specific resistance of the material at the given temperature:
+        // rho(T) = rho20 * (1 + alpha * (T - 20 °C))
+        if let Some(temperature) = elect.temperature {
+            match (elect.rho, elect.alpha) {
+                (Some(rho), Some(alpha)) => {
+                    elect.rho = Some(rho * (1f64 + alpha * (temperature - 20f64)))
+                }
+                _ => return Err("give me a material for the temperature"),
             }
         }
         Ok(elect)
@@ -339,8 +589,8 @@ impl Elect {
         };
         let mut num: i8 = -1i8;
         if counter == 3 {
-            for i in 0..4 {
-                if boolarr[i] {
+            for (i, given) in boolarr.iter().enumerate() {
+                if !*given {
                     match i {
                         0 => {
                             self.calc_resistor();
@@ -367,7 +617,7 @@ impl Elect {
         };
 
         if should_print {
-            match num.clone() {
+            match num {
                 0 => self.print_res(0i8),
                 1 => self.print_res(1i8),
                 2 => self.print_res(2i8),
@@ -379,30 +629,18 @@ impl Elect {
     }
     fn print_res(&mut self, num: i8) {
         let a: f64 = if let Some(i) = self.area.clone() {
-            if let Some(a) = i.a { a } else { 0f64 }
+            i.a.unwrap_or(0f64)
         } else {
             0f64
         };
         let d: f64 = if let Some(i) = self.area.clone() {
-            if let Some(d) = i.d { d } else { 0f64 }
-        } else {
-            0f64
-        };
-        let rho: f64 = if let Some(i) = self.rho.clone() {
-            i
-        } else {
-            0f64
-        };
-        let res: f64 = if let Some(i) = self.resistor.clone() {
-            i
-        } else {
-            0f64
-        };
-        let len: f64 = if let Some(i) = self.length.clone() {
-            i
+            i.d.unwrap_or(0f64)
         } else {
             0f64
         };
+        let rho: f64 = self.rho.unwrap_or(0f64);
+        let res: f64 = self.resistor.unwrap_or(0f64);
+        let len: f64 = self.length.unwrap_or(0f64);
         match num {
             0 => {
                 println!(
@@ -446,6 +684,16 @@ impl Elect {
             }
             _ => {}
         }
+        // the inductance of the wire and with the return wire also the capacitance. a film or the
+        // copper of a pcb isn't round, so there is no wire
+        if (0..=3).contains(&num) && len > 0f64 && d > 0f64 && self.thickness.is_none() {
+            let er = self.er.unwrap_or(1f64);
+            inductance::print_wire(len, d / 1000f64, self.spacing, er)
+        }
+        // the specific resistance in the other forms, if it is the result or a film is given
+        if num == 1 || self.thickness.is_some() {
+            resistivity::print_forms(rho, self.thickness)
+        }
     }
     fn calc_resistor(&mut self) {
         let a: f64 = if let Some(mut area) = self.area.clone() {
@@ -453,23 +701,15 @@ impl Elect {
                 a
             } else {
                 area.calc_area();
-                if let Some(a) = area.a { a } else { f64::NAN }
+                area.a.unwrap_or(f64::NAN)
             }
         } else {
             f64::NAN
         };
-        let rho: f64 = if let Some(rho) = self.rho.clone() {
-            rho
-        } else {
-            f64::NAN
-        };
-        let length: f64 = if let Some(len) = self.length.clone() {
-            len
-        } else {
-            f64::NAN
-        };
+        let rho: f64 = self.rho.unwrap_or(f64::NAN);
+        let length: f64 = self.length.unwrap_or(f64::NAN);
         self.resistor = if !a.is_nan() && !rho.is_nan() && !length.is_nan() {
-            if a < -0.000000001f64 || a > 0.000000001f64 {
+            if !(-0.000000001f64..=0.000000001f64).contains(&a) {
                 Some(rho * (length / a))
             } else {
                 None
@@ -484,23 +724,15 @@ impl Elect {
                 a
             } else {
                 area.calc_area();
-                if let Some(a) = area.a { a } else { f64::NAN }
+                area.a.unwrap_or(f64::NAN)
             }
         } else {
             f64::NAN
         };
-        let length: f64 = if let Some(len) = self.length.clone() {
-            len
-        } else {
-            f64::NAN
-        };
-        let resistor: f64 = if let Some(res) = self.resistor.clone() {
-            res
-        } else {
-            f64::NAN
-        };
+        let length: f64 = self.length.unwrap_or(f64::NAN);
+        let resistor: f64 = self.resistor.unwrap_or(f64::NAN);
         self.rho = if !a.is_nan() && !length.is_nan() && !resistor.is_nan() {
-            if length < -0.000000001f64 || length > 0.000000001f64 {
+            if !(-0.000000001f64..=0.000000001f64).contains(&length) {
                 Some((resistor * a) / length)
             } else {
                 None
@@ -515,23 +747,15 @@ impl Elect {
                 a
             } else {
                 area.calc_area();
-                if let Some(a) = area.a { a } else { f64::NAN }
+                area.a.unwrap_or(f64::NAN)
             }
         } else {
             f64::NAN
         };
-        let rho: f64 = if let Some(rho) = self.rho.clone() {
-            rho
-        } else {
-            f64::NAN
-        };
-        let resistor: f64 = if let Some(res) = self.resistor.clone() {
-            res
-        } else {
-            f64::NAN
-        };
+        let rho: f64 = self.rho.unwrap_or(f64::NAN);
+        let resistor: f64 = self.resistor.unwrap_or(f64::NAN);
         self.length = if !a.is_nan() && !rho.is_nan() && !resistor.is_nan() {
-            if rho < -0.000000001f64 || rho > 0.000000001f64 {
+            if !(-0.000000001f64..=0.000000001f64).contains(&rho) {
                 Some((resistor * a) / rho)
             } else {
                 None
@@ -541,23 +765,11 @@ impl Elect {
         };
     }
     fn calc_area(&mut self) {
-        let rho: f64 = if let Some(rho) = self.rho.clone() {
-            rho
-        } else {
-            f64::NAN
-        };
-        let length: f64 = if let Some(len) = self.length.clone() {
-            len
-        } else {
-            f64::NAN
-        };
-        let resistor: f64 = if let Some(res) = self.resistor.clone() {
-            res
-        } else {
-            f64::NAN
-        };
+        let rho: f64 = self.rho.unwrap_or(f64::NAN);
+        let length: f64 = self.length.unwrap_or(f64::NAN);
+        let resistor: f64 = self.resistor.unwrap_or(f64::NAN);
         self.area = if !rho.is_nan() && !length.is_nan() && !resistor.is_nan() {
-            if resistor < -0.000000001f64 || resistor > 0.000000001f64 {
+            if !(-0.000000001f64..=0.000000001f64).contains(&resistor) {
                 Some(Area {
                     d: None,
                     a: Some((rho * length) / resistor),
@@ -571,8 +783,8 @@ impl Elect {
     }
 }
 #[derive(Clone, Debug)]
-
-
+// URI is U = R * I, not an acronym clippy should rename
+#[allow(clippy::upper_case_acronyms)]
 struct URI {
     u: Option<f64>,
     i: Option<f64>,
@@ -587,9 +799,10 @@ impl URI {
         };
         for i in s.iter() {
             lazy_static! {
-                static ref RE: Regex = Regex::new(r"([0-9\.]+)(ohm|[Aa]|[Vv])").unwrap();
+                static ref RE: Regex =
+                    Regex::new(r"^(-?[0-9\.]+(?:e-?[0-9]+)?)(ohm|[Aa]|[Vv])$").unwrap();
             }
-            let caps = RE.captures(&i);
+            let caps = RE.captures(i.trim());
             let num: String;
             let einheit: String;
             if let Some(caps) = caps {
@@ -600,27 +813,19 @@ impl URI {
                 einheit = "".to_string();
             }
             let einheit = einheit.as_str();
+            // the sign is read too, otherwise -12V would be taken as 12 V
+            if num.starts_with('-') {
+                return Err("U, I and R can't be negative");
+            }
             match einheit {
                 "ohm" => {
-                    uri.r = if let Ok(i) = num.parse::<f64>() {
-                        Some(i)
-                    } else {
-                        None
-                    }
+                    uri.r = num.parse::<f64>().ok()
                 }
                 "v" => {
-                    uri.u = if let Ok(i) = num.parse::<f64>() {
-                        Some(i)
-                    } else {
-                        None
-                    }
+                    uri.u = num.parse::<f64>().ok()
                 }
                 "a" => {
-                    uri.i = if let Ok(i) = num.parse::<f64>() {
-                        Some(i)
-                    } else {
-                        None
-                    }
+                    uri.i = num.parse::<f64>().ok()
                 }
                 _ => return Err("input is strange"),
             }
@@ -650,8 +855,8 @@ impl URI {
         };
         let mut num: i8 = -1i8;
         if counter == 2 {
-            for i in 0..3 {
-                if !boolarr[i] {
+            for (i, given) in boolarr.iter().enumerate() {
+                if !*given {
                     match i {
                         0 => {
                             self.calc_u();
@@ -677,21 +882,9 @@ impl URI {
         }
     }
     fn print_res(&self, num: i8) {
-        let r: f64 = if let Some(i) = self.r.clone() {
-            i
-        } else {
-            0f64
-        };
-        let u: f64 = if let Some(i) = self.u.clone() {
-            i
-        } else {
-            0f64
-        };
-        let i: f64 = if let Some(i) = self.i.clone() {
-            i
-        } else {
-            0f64
-        };
+        let r: f64 = self.r.unwrap_or(0f64);
+        let u: f64 = self.u.unwrap_or(0f64);
+        let i: f64 = self.i.unwrap_or(0f64);
         match num {
             0 => {
                 println!(
@@ -721,52 +914,27 @@ impl URI {
         }
     }
     fn calc_u(&mut self) {
-        let r: f64 = if let Some(i) = self.r.clone() {
-            i
-        } else {
-            f64::NAN
-        };
-        let i: f64 = if let Some(i) = self.i.clone() {
-            i
-        } else {
-            f64::NAN
-        };
-        println!("{} * {} = {}", r, i, r * i);
-        self.u = if r > 0f64 && i > 0f64 {
+        let r: f64 = self.r.unwrap_or(f64::NAN);
+        let i: f64 = self.i.unwrap_or(f64::NAN);
+        self.u = if r > 0f64 && i >= 0f64 {
             Some(r * i)
         } else {
             None
         };
     }
     fn calc_i(&mut self) {
-        let r: f64 = if let Some(i) = self.r.clone() {
-            i
-        } else {
-            f64::NAN
-        };
-        let u: f64 = if let Some(i) = self.u.clone() {
-            i
-        } else {
-            f64::NAN
-        };
-        self.i = if r > 0f64 && u > 0f64 {
+        let r: f64 = self.r.unwrap_or(f64::NAN);
+        let u: f64 = self.u.unwrap_or(f64::NAN);
+        self.i = if r > 0f64 && u >= 0f64 {
             Some(u / r)
         } else {
             None
         };
     }
     fn calc_r(&mut self) {
-        let u: f64 = if let Some(i) = self.u.clone() {
-            i
-        } else {
-            f64::NAN
-        };
-        let i: f64 = if let Some(i) = self.i.clone() {
-            i
-        } else {
-            f64::NAN
-        };
-        self.r = if u > 0f64 && i > 0f64 {
+        let u: f64 = self.u.unwrap_or(f64::NAN);
+        let i: f64 = self.i.unwrap_or(f64::NAN);
+        self.r = if u >= 0f64 && i > 0f64 {
             Some(u / i)
         } else {
             None
@@ -790,80 +958,62 @@ impl elect_dual {
 }
 */
 fn main() {
-/*    println!(
-        "What do you want to calculate:\n1 for wire resistens,\n2 for uri,\n3 for two different wires."
-    );
-    let decision: char = read!();
-    match decision {
-        '1' => {
-            println!("please give me three strings");
-            let mut inputstrings: Vec<String> = Vec::new();
-            for _i in 0..3 {
-                let mut buf_string = String::new();
-                match io::stdin().read_line(&mut buf_string) {
-                    Ok(_n) => {
-                        inputstrings.push(String::from(buf_string.trim().clone()).replace("*", "x"))
-                    }
-                    Err(error) => {
-                        println!("error: {}", error);
-                    }
-                }
-            }
-            let mut elect = match Elect::new(inputstrings) {
-                Ok(n) => n,
-                Err(error) => {
-                    println!("error: {}!", error);
-                    panic!("cannot continue");
-                }
-            };
-            elect.calc(true);
-        }
-        '2' => {
-            println!("please give me two strings");
-            let mut inputstrings: Vec<String> = Vec::new();
-            for _i in 0..2 {
-                let mut buf_string = String::new();
-                match io::stdin().read_line(&mut buf_string) {
-                    Ok(_) => inputstrings.push(String::from(buf_string.trim().clone())),
-                    Err(error) => {
-                        println!("error: {}", error);
-                    }
-                }
-            }
-            let mut uri = match URI::new(inputstrings){
-                Ok(n) => n,
-                Err(error) => {
-                    println!("error: {}!", error);
-                    panic!("cannot continue");
-                }
-            };
-            uri.calc(true);
-        }
-        _ => {}
+    let args: Vec<String> = env::args().skip(1).collect();
+    process::exit(cli::run(args));
+}
+
+// the helpers for the tests of all the calculations
+#[cfg(test)]
+mod testing {
+    // the given values like they come from the command line
+    pub fn strings(given: &[&str]) -> Vec<String> {
+        given.iter().map(|given| given.to_string()).collect()
+    }
+    // new and calc of a calculation without printing, the results stay in the struct
+    pub fn calculated<T>(
+        given: &[&str],
+        new: fn(Vec<String>) -> Result<T, &'static str>,
+        calc: fn(&mut T, bool) -> Result<(), &'static str>,
+    ) -> Result<T, &'static str> {
+        let mut calculation = new(strings(given))?;
+        calc(&mut calculation, false)?;
+        Ok(calculation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pre_units_and_rkm() {
+        assert_eq!(parse_value("4.7kohm", &["ohm"]), Ok(4700f64));
+        assert!((parse_value("100nF", &["F"]).unwrap() - 100e-9f64).abs() < 1e-20);
+        assert!((parse_value("22pF", &["F"]).unwrap() - 22e-12f64).abs() < 1e-24);
+        assert!((parse_value("20 mA", &["A"]).unwrap() - 0.02f64).abs() < 1e-15);
+        assert_eq!(parse_value("4k7", &["ohm"]), Ok(4700f64));
+        assert!((parse_value("2R2ohm", &["ohm"]).unwrap() - 2.2f64).abs() < 1e-15);
+        // mm2 is a unit with a pre unit and no rkm code
+        assert!((parse_value("1.5mm2", &["mm2"]).unwrap() - 1.5f64).abs() < 1e-15);
+        assert_eq!(parse_value("4.7xohm", &["ohm"]), Err("unit not known"));
+        assert_eq!(parse_value("ohm", &["ohm"]), Err("number not parseable"));
+        assert!((parse_value("2*15m + 3m", &["m"]).unwrap() - 33f64).abs() < 1e-12);
+    }
+
+    #[test]
+    fn formatted_values() {
+        assert_eq!(format_value(4700f64, "Ω"), "4.7 kΩ");
+        assert_eq!(format_value(1e-4f64, "A"), "100 µA");
+        assert_eq!(format_value(0.2609f64, "A"), "260.9 mA");
+        assert_eq!(format_value(0f64, "V"), "0 V");
+        assert_eq!(format_value(2e20f64, "V"), "2.000e20 V");
+        assert_eq!(format_value(-3.3f64, "V"), "-3.3 V");
+    }
+
+    #[test]
+    fn given_names() {
+        assert_eq!(split_given("UL=400V"), ("ul".to_string(), "400V".to_string()));
+        assert_eq!(split_given("r: 10ohm"), ("r".to_string(), "10ohm".to_string()));
+        assert_eq!(split_given("copper"), ("".to_string(), "copper".to_string()));
     }
-*/
-    let (given,searched) : (String, String) = {
-        let mut given_option:Option<String> = None;
-        let mut searched_option:Option<String> = None;
-        while given_option.is_none() && searched_option.is_none(){
-            println!("give my an input for the given values with semicolon as separator(;) and the units with an colon(:)");
-            println!("You can give me the material name in english or german with material=material name.");
-            given_option = {
-                let mut buf_string = String::new();
-                match io::stdin().read_line(&mut buf_string) {
-                    Ok(_) => Some(String::from(buf_string.trim().clone())),
-                    Err(error) => None,
-                }
-            };
-            println!("And now please enter the variable with the unit we are searching for");
-            searched_option = {
-                let mut buf_string = String::new();
-                match io::stdin().read_line(&mut buf_string){
-                    Ok(_) => Some(String::from(buf_string.trim().clone())),
-                    Err(error) => None,
-                }
-            };
-        }
-        (given_option.unwrap(),searched_option.unwrap())
-    };
 }
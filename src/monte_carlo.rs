@@ -0,0 +1,258 @@
+// monte carlo analysis of Elect and URI. every given value with a tolerance is drawn at random:
+// a band like ±5% uniform in the band, a standard deviation like ±1%σ normal distributed (and
+// cut at truncate·σ if wanted). the results show the spread we have to expect in the production
+use std::f64;
+
+use super::{parse_value, split_given};
+use tolerance::{Calculation, Given};
+
+// a small xorshift random number generator, so a run can be repeated with the same seed
+#[derive(Clone, Debug)]
+pub struct Random {
+    state: u64,
+}
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        let mut random = Random {
+            state: seed ^ 0x9E37_79B9_7F4A_7C15,
+        };
+        // the state must never be zero
+        if random.state == 0 {
+            random.state = 1;
+        }
+        // the first numbers of small seeds are not random enough
+        for _ in 0..8 {
+            random.next_u64();
+        }
+        random
+    }
+    fn next_u64(&mut self) -> u64 {
+        // xorshift64*
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+    // uniform in [0, 1)
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+    // standard normal distributed with the box-muller method
+    pub fn normal(&mut self) -> f64 {
+        let u1 = 1f64 - self.uniform(); // (0, 1], so the logarithm is finite
+        let u2 = self.uniform();
+        (-2f64 * u1.ln()).sqrt() * (2f64 * f64::consts::PI * u2).cos()
+    }
+    // standard normal, but only between -limit and limit
+    pub fn truncated_normal(&mut self, limit: f64) -> f64 {
+        loop {
+            let z = self.normal();
+            if z.abs() <= limit {
+                return z;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MonteCarlo {
+    calculation: Calculation,
+    given: Vec<Given>,
+    iterations: usize,
+    seed: u64,
+    truncate: Option<f64>, //σ
+    min: Option<f64>,      //spec limits in the base unit of the result
+    max: Option<f64>,
+    results: Vec<f64>,
+    index: usize,
+}
+impl MonteCarlo {
+    pub fn new(s: Vec<String>, calculation: Calculation) -> Result<MonteCarlo, &'static str> {
+        let mut monte_carlo = MonteCarlo {
+            calculation,
+            given: Vec::new(),
+            iterations: 10000,
+            seed: 1,
+            truncate: None,
+            min: None,
+            max: None,
+            results: Vec::new(),
+            index: 0,
+        };
+        // every unit a result can have, for the spec limits
+        let units = ["ohmxmm2/m", "mm2", "mm", "ohm", "Ω", "m", "V", "A"];
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "n" | "iterations" => {
+                    monte_carlo.iterations = match value.parse::<usize>() {
+                        Ok(n) if n > 0 => n,
+                        _ => return Err("number of iterations not parseable"),
+                    }
+                }
+                "seed" => {
+                    monte_carlo.seed = match value.parse::<u64>() {
+                        Ok(seed) => seed,
+                        Err(_) => return Err("seed not parseable"),
+                    }
+                }
+                "truncate" => monte_carlo.truncate = Some(parse_value(&value, &["σ"])?),
+                "min" => monte_carlo.min = Some(parse_value(&value, &units)?),
+                "max" => monte_carlo.max = Some(parse_value(&value, &units)?),
+                _ => monte_carlo.given.push(Given::new(i, calculation)?),
+            }
+        }
+        // truncated_normal draws until a value is inside the limit, below 0.1σ that takes forever
+        if let Some(truncate) = monte_carlo.truncate {
+            if truncate.is_nan() || truncate < 0.1f64 {
+                return Err("truncate has to be at least 0.1σ");
+            }
+        }
+        Ok(monte_carlo)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        let mut random = Random::new(self.seed);
+        self.results.clear();
+        'draws: for _ in 0..self.iterations {
+            let mut strings: Vec<String> = Vec::new();
+            for given in self.given.iter() {
+                let factor = if let Some(tolerance) = given.tolerance {
+                    let z = if tolerance.sigma {
+                        if let Some(limit) = self.truncate {
+                            random.truncated_normal(limit)
+                        } else {
+                            random.normal()
+                        }
+                    } else {
+                        2f64 * random.uniform() - 1f64
+                    };
+                    1f64 + tolerance.relative * z
+                } else {
+                    1f64
+                };
+                // a draw of zero or below (like a negative length) is left out, Elect and URI
+                // have no result for it. only a temperature can be negative
+                if given.unit != "°C" && given.value.is_some_and(|value| value * factor <= 0f64) {
+                    continue 'draws;
+                }
+                strings.push(given.scaled(factor));
+            }
+            if let Some((index, value)) = self.calculation.evaluate(strings) {
+                self.index = index;
+                self.results.push(value);
+            }
+        }
+        if self.results.is_empty() {
+            return Err("the calculation has no result");
+        }
+        self.results
+            .sort_by(|a, b| a.partial_cmp(b).unwrap_or(::std::cmp::Ordering::Equal));
+        if should_print {
+            self.print_res()
+        }
+        Ok(())
+    }
+    // the nearest rank percentile of the sorted results
+    fn percentile(&self, p: f64) -> f64 {
+        let rank = (p / 100f64 * self.results.len() as f64).ceil() as usize;
+        self.results[rank.max(1).min(self.results.len()) - 1]
+    }
+    fn print_res(&self) {
+        let (name, unit) = self.calculation.result_name(self.index);
+        let n = self.results.len() as f64;
+        let mean = self.results.iter().fold(0f64, |sum, x| sum + x) / n;
+        let variance = self
+            .results
+            .iter()
+            .fold(0f64, |sum, x| sum + (x - mean).powi(2))
+            / (n - 1f64).max(1f64);
+        let sd = variance.sqrt();
+        println!(
+            "{} iterations of {} with the seed {}:",
+            self.results.len(),
+            name,
+            self.seed
+        );
+        println!("mean: {} {}", mean, unit);
+        println!(
+            "standard deviation: {} {} ({:.3} %)",
+            sd,
+            unit,
+            sd / mean.abs() * 100f64
+        );
+        println!(
+            "min: {} {}, max: {} {}",
+            self.results[0],
+            unit,
+            self.results[self.results.len() - 1],
+            unit
+        );
+        for p in [1f64, 5f64, 50f64, 95f64, 99f64].iter() {
+            println!("{} % percentile: {} {}", p, self.percentile(*p), unit);
+        }
+        if self.min.is_some() || self.max.is_some() {
+            let min = self.min.unwrap_or(f64::NEG_INFINITY);
+            let max = self.max.unwrap_or(f64::INFINITY);
+            let good = self
+                .results
+                .iter()
+                .filter(|x| **x >= min && **x <= max)
+                .count();
+            println!(
+                "yield between {} and {} {}: {} % ({} of {})",
+                min,
+                max,
+                unit,
+                good as f64 / n * 100f64,
+                good,
+                self.results.len()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut a = Random::new(42);
+        let mut b = Random::new(42);
+        for _ in 0..100 {
+            let uniform = a.uniform();
+            assert_eq!(uniform, b.uniform());
+            assert!((0f64..1f64).contains(&uniform));
+        }
+    }
+
+    #[test]
+    fn truncated_normal_stays_inside() {
+        let mut random = Random::new(7);
+        for _ in 0..1000 {
+            assert!(random.truncated_normal(0.5f64).abs() <= 0.5f64);
+        }
+    }
+
+    #[test]
+    fn truncate_has_to_be_positive() {
+        for truncate in ["truncate=0", "truncate=-1", "truncate=0σ"].iter() {
+            let given = vec!["r=1kohm±1%".to_string(), truncate.to_string()];
+            assert!(MonteCarlo::new(given, Calculation::Uri).is_err());
+        }
+    }
+
+    #[test]
+    fn draws_below_zero_are_left_out() {
+        let given = vec!["u=1V±100%σ".to_string(), "r=1ohm".to_string(), "n=2000".to_string()];
+        let mut monte_carlo = MonteCarlo::new(given, Calculation::Uri).unwrap();
+        monte_carlo.calc(false).unwrap();
+        // about 16 % of N(1, 1) is below zero
+        let kept = monte_carlo.results.len();
+        assert!(kept > 1500 && kept < 1800);
+        assert!(monte_carlo.results.iter().all(|current| *current > 0f64));
+        // the mean of the positive part of N(1, 1) is 1.288, the mean of |N(1, 1)| would be 1.167
+        let mean = monte_carlo.results.iter().sum::<f64>() / kept as f64;
+        assert!((mean - 1.288f64).abs() < 0.05f64);
+    }
+}
@@ -0,0 +1,374 @@
+// capacitors and inductors: decoding of the markings, series and parallel connections and the
+// stored energy. together with colour_code this covers the passive components
+use std::f64;
+
+use super::{parse_value, split_given};
+use colour_code::{digits_value, Colour};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Connection {
+    Series,
+    Parallel,
+}
+
+// a decoded marking of a capacitor like "104", "4n7" or "2A104J"
+#[derive(Clone, Debug)]
+struct CapacitorMarking {
+    marking: String,
+    capacitance: f64,     //F
+    voltage: Option<f64>, //V
+    tolerance: Option<&'static str>,
+}
+impl CapacitorMarking {
+    fn new(marking: &str) -> Result<CapacitorMarking, &'static str> {
+        let mut rest: String = marking.trim().to_string();
+        // the eia voltage code in front, 2A is 100 V
+        let mut voltage = None;
+        if rest.len() > 2 && rest.is_char_boundary(2) {
+            if let Some(v) = voltage_code(&rest[..2].to_uppercase()) {
+                voltage = Some(v);
+                rest = rest[2..].to_string();
+            }
+        }
+        // the tolerance letter at the end, but only after a digit, 4n7 has no tolerance
+        let mut tolerance = None;
+        let chars: Vec<char> = rest.chars().collect();
+        if chars.len() > 1 && chars[chars.len() - 2].is_ascii_digit() {
+            if let Some(t) = tolerance_letter(chars[chars.len() - 1]) {
+                tolerance = Some(t);
+                rest = chars[..chars.len() - 1].iter().collect();
+            }
+        }
+        Ok(CapacitorMarking {
+            marking: marking.to_string(),
+            capacitance: decode_value(&rest, -12)?,
+            voltage,
+            tolerance,
+        })
+    }
+}
+
+// a decoded marking of an inductor. small inductors are marked like resistors, but in µH
+#[derive(Clone, Debug)]
+struct InductorMarking {
+    marking: String,
+    inductance: f64, //H
+    tolerance: Option<String>,
+}
+impl InductorMarking {
+    fn new(marking: &str) -> Result<InductorMarking, &'static str> {
+        let colours: Vec<&str> = marking
+            .split([' ', '-', ','])
+            .filter(|colour| !colour.is_empty())
+            .collect();
+        // colour bands: digit, digit, multiplier and the tolerance
+        if colours.len() >= 3 {
+            let mut bands: Vec<Colour> = Vec::new();
+            for colour in colours.iter() {
+                bands.push(Colour::new(colour)?);
+            }
+            let significant = bands[0].digit()? as u32 * 10 + bands[1].digit()? as u32;
+            let tolerance = if bands.len() > 3 {
+                Some(format!("± {} %", bands[3].tolerance()?))
+            } else {
+                Some("± 20 %".to_string())
+            };
+            return Ok(InductorMarking {
+                marking: marking.to_string(),
+                inductance: digits_value(significant, bands[2].multiplier()? - 6),
+                tolerance,
+            });
+        }
+        let mut rest: String = marking.trim().to_string();
+        let mut tolerance = None;
+        let chars: Vec<char> = rest.chars().collect();
+        if chars.len() > 1 && chars[chars.len() - 2].is_ascii_digit() {
+            if let Some(t) = tolerance_letter(chars[chars.len() - 1]) {
+                tolerance = Some(t.to_string());
+                rest = chars[..chars.len() - 1].iter().collect();
+            }
+        }
+        // R is the decimal point in µH like at the smd resistors
+        let rest = rest.replace("R", "u").replace("r", "u");
+        Ok(InductorMarking {
+            marking: marking.to_string(),
+            inductance: decode_value(&rest, -6)?,
+            tolerance,
+        })
+    }
+}
+
+// decodes the value of a marking. three digits are two significant digits and the power of ten
+// in the base unit (pF for capacitors, µH for inductors), base_pow is the power of ten of it. a pre
+// unit letter can also be the decimal point like in 4n7 or 2u2
+fn decode_value(value: &str, base_pow: i32) -> Result<f64, &'static str> {
+    let value = value.trim();
+    let position = value.find(|c: char| {
+        c == 'p' || c == 'n' || c == 'u' || c == 'µ' || c == 'm' || c == 'N' || c == 'U'
+    });
+    if let Some(position) = position {
+        let letter = value[position..].chars().next().unwrap_or('p');
+        let number = value.replacen(letter, ".", 1);
+        let number = number.trim_end_matches('.');
+        let pow = match letter {
+            'p' => 12,
+            'n' | 'N' => 9,
+            'u' | 'U' | 'µ' => 6,
+            _ => 3,
+        };
+        return match number.parse::<f64>() {
+            Ok(number) => Ok(number / 10f64.powi(pow)),
+            Err(_) => Err("marking not parseable"),
+        };
+    }
+    if value.len() == 3 && value.chars().all(|c| c.is_ascii_digit()) {
+        let significant = value[..2].parse::<u32>().unwrap_or(0);
+        // 8 and 9 as multiplier are 0.01 and 0.1
+        let pow = match value[2..].parse::<i32>().unwrap_or(0) {
+            8 => -2,
+            9 => -1,
+            pow => pow,
+        };
+        return Ok(digits_value(significant, pow + base_pow));
+    }
+    match value.parse::<f64>() {
+        // old capacitors: values below 1 are in µF
+        Ok(number) if number < 1f64 && base_pow == -12 => Ok(number / 1e6f64),
+        Ok(number) => Ok(number / 10f64.powi(-base_pow)),
+        Err(_) => Err("marking not parseable"),
+    }
+}
+
+// the eia voltage codes of capacitors
+fn voltage_code(code: &str) -> Option<f64> {
+    match code {
+        "0G" => Some(4f64),
+        "0L" => Some(5.5f64),
+        "0J" => Some(6.3f64),
+        "1A" => Some(10f64),
+        "1C" => Some(16f64),
+        "1D" => Some(20f64),
+        "1E" => Some(25f64),
+        "1V" => Some(35f64),
+        "1H" => Some(50f64),
+        "1J" => Some(63f64),
+        "1K" => Some(80f64),
+        "2A" => Some(100f64),
+        "2Q" => Some(110f64),
+        "2B" => Some(125f64),
+        "2C" => Some(160f64),
+        "2Z" => Some(180f64),
+        "2D" => Some(200f64),
+        "2P" => Some(220f64),
+        "2E" => Some(250f64),
+        "2F" => Some(315f64),
+        "2V" => Some(350f64),
+        "2G" => Some(400f64),
+        "2W" => Some(450f64),
+        "2H" => Some(500f64),
+        "2J" => Some(630f64),
+        "3A" => Some(1000f64),
+        _ => None,
+    }
+}
+
+// the tolerance letters, B to D are absolute values for small capacitors
+fn tolerance_letter(letter: char) -> Option<&'static str> {
+    match letter {
+        'B' => Some("± 0.1 pF"),
+        'C' => Some("± 0.25 pF"),
+        'D' => Some("± 0.5 pF"),
+        'F' => Some("± 1 %"),
+        'G' => Some("± 2 %"),
+        'J' => Some("± 5 %"),
+        'K' => Some("± 10 %"),
+        'M' => Some("± 20 %"),
+        'Z' => Some("+80 % / -20 %"),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Passive {
+    connection: Connection,
+    capacitors: Vec<f64>, //F
+    inductors: Vec<f64>,  //H
+    capacitor_markings: Vec<CapacitorMarking>,
+    inductor_markings: Vec<InductorMarking>,
+    u: Option<f64>,       //V across the capacitors
+    i: Option<f64>,       //A through the inductors
+    c_total: Option<f64>, //F
+    l_total: Option<f64>, //H
+}
+impl Passive {
+    pub fn new(s: Vec<String>) -> Result<Passive, &'static str> {
+        let mut passive = Passive {
+            connection: Connection::Parallel,
+            capacitors: Vec::new(),
+            inductors: Vec::new(),
+            capacitor_markings: Vec::new(),
+            inductor_markings: Vec::new(),
+            u: None,
+            i: None,
+            c_total: None,
+            l_total: None,
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "c" => passive.capacitors.push(parse_value(&value, &["F"])?),
+                "l" => passive.inductors.push(parse_value(&value, &["H"])?),
+                "cap" | "ccode" => {
+                    let marking = CapacitorMarking::new(&value)?;
+                    passive.capacitors.push(marking.capacitance);
+                    passive.capacitor_markings.push(marking);
+                }
+                "ind" | "lcode" => {
+                    let marking = InductorMarking::new(&value)?;
+                    passive.inductors.push(marking.inductance);
+                    passive.inductor_markings.push(marking);
+                }
+                "u" => passive.u = Some(parse_value(&value, &["V"])?),
+                "i" => passive.i = Some(parse_value(&value, &["A"])?),
+                "" => {
+                    passive.connection = match value.to_lowercase().as_str() {
+                        "series" | "reihe" => Connection::Series,
+                        "parallel" => Connection::Parallel,
+                        _ => return Err("connection not known"),
+                    }
+                }
+                _ => return Err("input is strange"),
+            }
+        }
+        if passive.capacitors.is_empty() && passive.inductors.is_empty() {
+            return Err("give me at least one capacitor or inductor");
+        }
+        Ok(passive)
+    }
+    // capacitors in parallel and inductors in series are added, the other way round the
+    // reciprocal values are added. the inductors are taken as not coupled
+    pub fn calc(&mut self, should_print: bool) {
+        let sum = |values: &Vec<f64>| values.iter().fold(0f64, |sum, value| sum + value);
+        let reciprocal_sum = |values: &Vec<f64>| {
+            1f64 / values.iter().fold(0f64, |sum, value| sum + 1f64 / value)
+        };
+        if !self.capacitors.is_empty() {
+            self.c_total = Some(match self.connection {
+                Connection::Parallel => sum(&self.capacitors),
+                Connection::Series => reciprocal_sum(&self.capacitors),
+            });
+        }
+        if !self.inductors.is_empty() {
+            self.l_total = Some(match self.connection {
+                Connection::Series => sum(&self.inductors),
+                Connection::Parallel => reciprocal_sum(&self.inductors),
+            });
+        }
+        if should_print {
+            self.print_res()
+        }
+    }
+    fn print_res(&self) {
+        for marking in self.capacitor_markings.iter() {
+            print!("The capacitor marking {} is: {} F", marking.marking, marking.capacitance);
+            if let Some(tolerance) = marking.tolerance {
+                print!(" {}", tolerance);
+            }
+            if let Some(voltage) = marking.voltage {
+                print!(" for {} V", voltage);
+            }
+            println!();
+        }
+        for marking in self.inductor_markings.iter() {
+            print!("The inductor marking {} is: {} H", marking.marking, marking.inductance);
+            if let Some(tolerance) = marking.tolerance.clone() {
+                print!(" {}", tolerance);
+            }
+            println!();
+        }
+        let connection = match self.connection {
+            Connection::Series => "series",
+            Connection::Parallel => "parallel",
+        };
+        if let Some(c_total) = self.c_total {
+            if self.capacitors.len() > 1 {
+                println!(
+                    "The {} connection of the {} capacitors is: {} F",
+                    connection,
+                    self.capacitors.len(),
+                    c_total
+                );
+            }
+            // W = C·U²/2 and Q = C·U
+            if let Some(u) = self.u {
+                println!(
+                    "At {} V the stored energy is: {} J and the charge is: {} C",
+                    u,
+                    c_total * u * u / 2f64,
+                    c_total * u
+                );
+            }
+        }
+        if let Some(l_total) = self.l_total {
+            if self.inductors.len() > 1 {
+                println!(
+                    "The {} connection of the {} inductors is: {} H",
+                    connection,
+                    self.inductors.len(),
+                    l_total
+                );
+            }
+            // W = L·I²/2
+            if let Some(i) = self.i {
+                println!("At {} A the stored energy is: {} J", i, l_total * i * i / 2f64);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::strings;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-9 * b.abs()
+    }
+
+    #[test]
+    fn capacitor_markings() {
+        assert!(close(CapacitorMarking::new("104").unwrap().capacitance, 100e-9f64));
+        assert!(close(CapacitorMarking::new("4n7").unwrap().capacitance, 4.7e-9f64));
+        assert!(close(CapacitorMarking::new("229").unwrap().capacitance, 2.2e-12f64));
+        // old capacitors without unit below 1 are in µF
+        assert!(close(CapacitorMarking::new("0.1").unwrap().capacitance, 0.1e-6f64));
+        let marking = CapacitorMarking::new("2A104J").unwrap();
+        assert!(close(marking.capacitance, 100e-9f64));
+        assert_eq!(marking.voltage, Some(100f64));
+        assert_eq!(marking.tolerance, Some("± 5 %"));
+        assert!(CapacitorMarking::new("x7").is_err());
+    }
+
+    #[test]
+    fn inductor_markings() {
+        assert!(close(InductorMarking::new("101").unwrap().inductance, 100e-6f64));
+        assert!(close(InductorMarking::new("2R2").unwrap().inductance, 2.2e-6f64));
+        let marking = InductorMarking::new("brown black brown silver").unwrap();
+        assert!(close(marking.inductance, 100e-6f64));
+        assert_eq!(marking.tolerance, Some("± 10 %".to_string()));
+    }
+
+    #[test]
+    fn series_and_parallel() {
+        let mut passive = Passive::new(strings(&["c=1uF", "c=1uF", "l=1mH", "l=3mH"])).unwrap();
+        passive.calc(false);
+        assert!(close(passive.c_total.unwrap(), 2e-6f64));
+        assert!(close(passive.l_total.unwrap(), 0.75e-3f64));
+        let given = ["series", "c=1uF", "c=1uF", "l=1mH", "l=3mH"];
+        let mut passive = Passive::new(strings(&given)).unwrap();
+        passive.calc(false);
+        assert!(close(passive.c_total.unwrap(), 0.5e-6f64));
+        assert!(close(passive.l_total.unwrap(), 4e-3f64));
+        assert!(Passive::new(vec!["series".to_string()]).is_err());
+    }
+}
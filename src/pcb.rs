@@ -0,0 +1,384 @@
+// conductors on printed circuit boards. the width of a trace for a current and a temperature rise
+// comes from the curves of IPC-2221:
+//   I = k * dT^0.44 * A^0.725    with I in A, dT in K and the cross section A in mil²
+// k is 0.048 for the outer and 0.024 for the inner layers, the copper is a flat rectangle with the
+// thickness of its weight (1 oz = 35 µm). the resistance of the trace is calculated with Elect
+// and copper at the temperature the trace gets warm to. a via is a copper tube through the board,
+// the plating is the wall of the tube:
+//   A = pi * (d - t) * t    with the drill diameter d and the plating thickness t
+// its current is taken from the same curve as an outer trace of this cross section
+use std::f64;
+
+use super::{format_value, parse_value, split_given, Elect};
+use resistivity::{parse_thickness, OUNCE};
+
+// one mil is a thousandth inch, in mm
+const MIL: f64 = 0.0254f64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Layer {
+    External,
+    Internal,
+}
+impl Layer {
+    fn new(name: &str) -> Result<Layer, &'static str> {
+        match name.to_lowercase().as_str() {
+            "external" | "outer" | "top" | "bottom" | "aussen" => Ok(Layer::External),
+            "internal" | "inner" | "innen" => Ok(Layer::Internal),
+            _ => Err("layer not known, give me external or internal"),
+        }
+    }
+    // the factor k of IPC-2221, the inner layers can't give their heat to the air
+    fn k(&self) -> f64 {
+        match self {
+            Layer::External => 0.048f64,
+            Layer::Internal => 0.024f64,
+        }
+    }
+}
+
+// the resistance of a copper conductor with Elect, area in mm², length in m and temperature in °C
+pub fn copper_resistance(area: f64, length: f64, temperature: f64) -> Result<f64, &'static str> {
+    let mut elect = Elect::new(vec![
+        "copper".to_string(),
+        format!("{}m", length),
+        format!("{}mm2", area),
+        format!("{}°C", temperature),
+    ])?;
+    elect.calc(false);
+    match elect.resistor {
+        Some(resistor) => Ok(resistor),
+        None => Err("the resistance of the copper is not calculable"),
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Trace {
+    pub current: Option<f64>, //A
+    width: Option<f64>,   //mm
+    rise: f64,            //K over the ambient temperature
+    layer: Layer,
+    thickness: f64,       //mm of the copper
+    length: Option<f64>,  //m
+    ambient: f64,         //°C
+    pub resistance: Option<f64>, //ohm over the length
+}
+impl Trace {
+    pub fn new(s: Vec<String>) -> Result<Trace, &'static str> {
+        let mut trace = Trace {
+            current: None,
+            width: None,
+            rise: 10f64,
+            layer: Layer::External,
+            thickness: OUNCE,
+            length: None,
+            ambient: 25f64,
+            resistance: None,
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "i" | "current" => trace.current = Some(parse_value(&value, &["A"])?),
+                "w" | "width" => trace.width = Some(parse_value(&value, &["m"])? * 1000f64),
+                "dt" | "rise" => trace.rise = parse_value(&value, &["K", "°C"])?,
+                "layer" => trace.layer = Layer::new(&value)?,
+                // a plain number is the copper weight in oz
+                "oz" | "weight" | "h" | "thickness" => {
+                    trace.thickness = match value.parse::<f64>() {
+                        Ok(ounces) if ounces > 0f64 => ounces * OUNCE,
+                        _ => parse_thickness(&value)?,
+                    }
+                }
+                "l" | "length" => trace.length = Some(parse_value(&value, &["m"])?),
+                "t" | "ambient" => trace.ambient = parse_value(&value, &["°C", "degC"])?,
+                "" => trace.layer = Layer::new(&value)?,
+                _ => return Err("input is strange"),
+            }
+        }
+        match (trace.current, trace.width) {
+            (Some(current), None) if current > 0f64 => {}
+            (None, Some(width)) if width > 0f64 => {}
+            (Some(_), Some(_)) => return Err("give me only one of the current and the width"),
+            _ => return Err("give me a positive current or width"),
+        }
+        if trace.rise <= 0f64 {
+            return Err("the temperature rise has to be positive");
+        }
+        Ok(trace)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        let thickness = self.thickness / MIL; //mil
+        let k = self.layer.k() * self.rise.powf(0.44f64);
+        match (self.current, self.width) {
+            // A = (I / (k * dT^0.44))^(1 / 0.725)
+            (Some(current), _) => {
+                let area = (current / k).powf(1f64 / 0.725f64); //mil²
+                self.width = Some(area / thickness * MIL);
+            }
+            (None, Some(width)) => {
+                let area = width / MIL * thickness; //mil²
+                self.current = Some(k * area.powf(0.725f64));
+            }
+            _ => return Err("give me a positive current or width"),
+        }
+        if let (Some(length), Some(width)) = (self.length, self.width) {
+            self.resistance = Some(copper_resistance(
+                width * self.thickness,
+                length,
+                self.ambient + self.rise,
+            )?);
+        }
+        if should_print {
+            self.print_res()
+        }
+        Ok(())
+    }
+    // the cross section in mm²
+    pub fn area(&self) -> Option<f64> {
+        self.width.map(|width| width * self.thickness)
+    }
+    fn print_res(&self) {
+        let width = self.width.unwrap_or(0f64);
+        let current = self.current.unwrap_or(0f64);
+        let layer = match self.layer {
+            Layer::External => "external",
+            Layer::Internal => "internal",
+        };
+        println!(
+            "the {} trace of {} ({:.1} mil) with {} copper carries {} at a rise of {} K",
+            layer,
+            format_value(width / 1000f64, "m"),
+            width / MIL,
+            format_value(self.thickness / 1000f64, "m"),
+            format_value(current, "A"),
+            self.rise
+        );
+        if let Some(area) = self.area() {
+            println!("the cross section is: {:.4} mm²", area);
+        }
+        if let (Some(resistance), Some(length)) = (self.resistance, self.length) {
+            println!(
+                "over {} at {} °C the resistance is: {}, the voltage drop: {} and the loss: {}",
+                format_value(length, "m"),
+                self.ambient + self.rise,
+                format_value(resistance, "Ω"),
+                format_value(current * resistance, "V"),
+                format_value(current * current * resistance, "W")
+            );
+        }
+        // the curves of IPC-2221 only go so far
+        if current > 35f64 || width > 400f64 * MIL || self.rise < 10f64 || self.rise > 100f64 {
+            println!("note: IPC-2221 is only measured up to 35 A, 400 mil and 10 K to 100 K rise");
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Via {
+    drill: Option<f64>, //mm
+    plating: f64,       //mm
+    board: f64,         //mm thickness of the board, the length of the via
+    rise: f64,          //K
+    ambient: f64,       //°C
+    current: Option<f64>, //A through the path
+    count: Option<u32>,   //vias in parallel, calculated from the current without
+    trace: Vec<String>,   //the given values of the trace to the via
+    pub resistance: Option<f64>, //ohm of one via
+    pub capacity: Option<f64>,   //A of one via
+}
+impl Via {
+    pub fn new(s: Vec<String>) -> Result<Via, &'static str> {
+        let mut via = Via {
+            drill: None,
+            plating: 0.025f64,
+            board: 1.6f64,
+            rise: 10f64,
+            ambient: 25f64,
+            current: None,
+            count: None,
+            trace: Vec::new(),
+            resistance: None,
+            capacity: None,
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "d" | "drill" => via.drill = Some(parse_value(&value, &["m"])? * 1000f64),
+                "plating" | "p" => via.plating = parse_thickness(&value)?,
+                "board" | "b" => via.board = parse_value(&value, &["m"])? * 1000f64,
+                "dt" | "rise" => via.rise = parse_value(&value, &["K", "°C"])?,
+                "t" | "ambient" => via.ambient = parse_value(&value, &["°C", "degC"])?,
+                "i" | "current" => via.current = Some(parse_value(&value, &["A"])?),
+                "n" => {
+                    via.count = match value.parse::<u32>() {
+                        Ok(n) if n > 0 => Some(n),
+                        _ => return Err("number of vias not parseable"),
+                    }
+                }
+                // the trace of the path is calculated with Trace
+                "w" | "width" | "l" | "length" | "oz" | "weight" | "h" | "thickness" | "layer" => {
+                    via.trace.push(format!("{}={}", name, value))
+                }
+                _ => return Err("input is strange"),
+            }
+        }
+        match via.drill {
+            Some(drill) if drill > 2f64 * via.plating && via.plating > 0f64 => {}
+            Some(_) => return Err("the plating has to be thinner than half the drill"),
+            None => return Err("give me the drill diameter"),
+        }
+        if via.board <= 0f64 || via.rise <= 0f64 {
+            return Err("the board thickness and the rise have to be positive");
+        }
+        Ok(via)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        let drill = self.drill.unwrap_or(f64::NAN);
+        let area = f64::consts::PI * (drill - self.plating) * self.plating; //mm²
+        let resistance = copper_resistance(area, self.board / 1000f64, self.ambient + self.rise)?;
+        let area_mil = area / (MIL * MIL);
+        let capacity = Layer::External.k() * self.rise.powf(0.44f64) * area_mil.powf(0.725f64);
+        self.resistance = Some(resistance);
+        self.capacity = Some(capacity);
+        if self.count.is_none() {
+            self.count = match self.current {
+                Some(current) => Some(((current / capacity).ceil() as u32).max(1)),
+                None => Some(1),
+            };
+        }
+        // the path of the trace and the vias in parallel
+        let trace = if self.trace.is_empty() {
+            None
+        } else {
+            let mut values = self.trace.clone();
+            values.push(format!("dt={}K", self.rise));
+            values.push(format!("t={}°C", self.ambient));
+            let mut trace = Trace::new(values)?;
+            trace.calc(false)?;
+            Some(trace)
+        };
+        if should_print {
+            self.print_res(area, trace)
+        }
+        Ok(())
+    }
+    fn print_res(&self, area: f64, trace: Option<Trace>) {
+        let resistance = self.resistance.unwrap_or(0f64);
+        let capacity = self.capacity.unwrap_or(0f64);
+        let count = self.count.unwrap_or(1);
+        println!(
+            "the via with {} drill and {} plating through {} has {:.4} mm² of copper",
+            format_value(self.drill.unwrap_or_default() / 1000f64, "m"),
+            format_value(self.plating / 1000f64, "m"),
+            format_value(self.board / 1000f64, "m"),
+            area
+        );
+        println!(
+            "its resistance is: {} at {} °C and it carries {} at a rise of {} K",
+            format_value(resistance, "Ω"),
+            self.ambient + self.rise,
+            format_value(capacity, "A"),
+            self.rise
+        );
+        if let Some(current) = self.current {
+            println!(
+                "for {} {} vias are needed, {} in parallel have {}",
+                format_value(current, "A"),
+                ((current / capacity).ceil() as u32).max(1),
+                count,
+                format_value(resistance / count as f64, "Ω")
+            );
+            if (count as f64) * capacity < current {
+                println!("note: {} vias are too few for this current", count);
+            }
+        }
+        if let Some(trace) = trace {
+            let trace_resistance = trace.resistance.unwrap_or(0f64);
+            let path = trace_resistance + resistance / count as f64;
+            println!(
+                "the trace has {} and carries {}, the path with {} vias has {}",
+                format_value(trace_resistance, "Ω"),
+                format_value(trace.current.unwrap_or_default(), "A"),
+                count,
+                format_value(path, "Ω")
+            );
+            if let Some(current) = self.current {
+                println!(
+                    "at {} the voltage drop of the path is: {} and the loss: {}",
+                    format_value(current, "A"),
+                    format_value(current * path, "V"),
+                    format_value(current * current * path, "W")
+                );
+                if current > trace.current.unwrap_or_default() {
+                    println!("note: the trace is too narrow for this current");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::{calculated, strings};
+
+    fn trace(given: &[&str]) -> Trace {
+        calculated(given, Trace::new, Trace::calc).unwrap()
+    }
+
+    fn via(given: &[&str]) -> Result<Via, &'static str> {
+        calculated(given, Via::new, Via::calc)
+    }
+
+    #[test]
+    fn ipc_2221_width() {
+        // 1 A, 10 K and 1 oz on an outer layer is the well known 0.3 mm (12 mil)
+        let outer = trace(&["i=1A"]);
+        let width = outer.width.unwrap();
+        let area = width / MIL * (OUNCE / MIL);
+        assert!((0.048f64 * 10f64.powf(0.44f64) * area.powf(0.725f64) - 1f64).abs() < 1e-9);
+        assert!((width - 0.302f64).abs() < 0.001f64);
+        // the inner layer has half the k, the area grows with 2^(1/0.725)
+        let inner = trace(&["i=1A", "inner"]);
+        assert!((inner.width.unwrap() / width - 2f64.powf(1f64 / 0.725f64)).abs() < 1e-9);
+        // and back from the width
+        let back = trace(&["w=0.302mm"]);
+        assert!((back.current.unwrap() - 1f64).abs() < 0.01f64);
+        let long = trace(&["i=1A", "l=0.1m"]);
+        let expected = copper_resistance(long.area().unwrap(), 0.1f64, 35f64).unwrap();
+        assert_eq!(long.resistance, Some(expected));
+    }
+
+    #[test]
+    fn trace_errors() {
+        let new = |given: &[&str]| Trace::new(strings(given));
+        assert!(new(&["i=1A", "w=1mm"]).is_err());
+        assert!(new(&["i=0A"]).is_err());
+        assert!(new(&["i=1A", "dt=0K"]).is_err());
+        assert!(new(&["i=1A", "layer=middle"]).is_err());
+    }
+
+    #[test]
+    fn via_current_and_count() {
+        // a 0.3 mm drill with 25 µm plating has a wall of pi * 0.275 mm * 0.025 mm
+        let one = via(&["d=0.3mm"]).unwrap();
+        let area = f64::consts::PI * 0.275f64 * 0.025f64;
+        let capacity = 0.048f64 * 10f64.powf(0.44f64) * (area / (MIL * MIL)).powf(0.725f64);
+        assert!((one.capacity.unwrap() - capacity).abs() < 1e-12);
+        assert!((one.capacity.unwrap() - 1.685f64).abs() < 0.001f64);
+        assert_eq!(one.count, Some(1));
+        let expected = copper_resistance(area, 0.0016f64, 35f64).unwrap();
+        assert!((one.resistance.unwrap() - expected).abs() < 1e-15);
+        assert_eq!(via(&["d=0.3mm", "i=5A"]).unwrap().count, Some(3));
+        assert_eq!(via(&["d=0.3mm", "i=5A", "n=2"]).unwrap().count, Some(2));
+        assert!(via(&["d=0.3mm", "i=5A", "w=2mm"]).is_ok());
+    }
+
+    #[test]
+    fn via_errors() {
+        assert!(via(&["i=1A"]).is_err());
+        assert!(via(&["d=0.05mm"]).is_err());
+        assert!(via(&["d=0.3mm", "n=0"]).is_err());
+        assert!(via(&["d=0.3mm", "b=0mm"]).is_err());
+    }
+}
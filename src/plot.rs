@@ -0,0 +1,497 @@
+// plots of a sweep, as ascii chart in the terminal and as svg file. the first swept variable is
+// the x axis, with a second one every value of it is its own series, like the resistance over the
+// temperature for some materials:
+//   t=-40°C..120°C:10°C; {copper, aluminium, iron}; 10m; 1.5mm2; log=y; file=wire.svg
+use std::f64;
+use std::fs::File;
+use std::io::Write;
+
+use super::{format_value, split_given};
+use sweep::{numeric, Sweep};
+use tolerance::Calculation;
+
+// the units as they are written on the axes
+fn pretty_unit(unit: &str) -> &str {
+    match unit {
+        "ohm" => "Ω",
+        "mm2" => "mm²",
+        "ohmxmm2/m" => "Ω·mm²/m",
+        _ => unit,
+    }
+}
+
+// only these units get a pre unit on the axes, 1 kmm² would be strange
+fn has_pre_unit(unit: &str) -> bool {
+    matches!(unit, "V" | "A" | "Ω" | "m" | "W")
+}
+
+// the markers of the series in the ascii chart and their colours in the svg
+static MARKERS: [char; 8] = ['*', 'o', '+', 'x', '#', '@', '%', '&'];
+static COLOURS: [&str; 8] = [
+    "#1f77b4", "#d62728", "#2ca02c", "#ff7f0e", "#9467bd", "#8c564b", "#e377c2", "#17becf",
+];
+
+// one axis with its range and scale
+#[derive(Clone, Debug)]
+struct Axis {
+    title: String,
+    unit: String,
+    log: bool,
+    min: f64, // in the scale, so the logarithm for a log axis
+    max: f64,
+}
+impl Axis {
+    fn scale(&self, value: f64) -> f64 {
+        if self.log {
+            value.log10()
+        } else {
+            value
+        }
+    }
+    // the position of the value between 0 and 1
+    fn position(&self, value: f64) -> f64 {
+        (self.scale(value) - self.min) / (self.max - self.min)
+    }
+    // round steps of 1, 2 and 5 or the decades for a log axis
+    fn ticks(&self) -> Vec<f64> {
+        let mut ticks: Vec<f64> = Vec::new();
+        if self.log {
+            for decade in (self.min.ceil() as i32)..(self.max.floor() as i32 + 1) {
+                ticks.push(10f64.powi(decade));
+            }
+            if ticks.len() >= 2 {
+                return ticks;
+            }
+            ticks.clear();
+        }
+        let (min, max) = if self.log {
+            (10f64.powf(self.min), 10f64.powf(self.max))
+        } else {
+            (self.min, self.max)
+        };
+        let raw = (max - min) / 5f64;
+        let magnitude = 10f64.powf(raw.log10().floor());
+        let step = [1f64, 2f64, 5f64, 10f64]
+            .iter()
+            .map(|m| m * magnitude)
+            .find(|step| *step >= raw)
+            .unwrap_or(10f64 * magnitude);
+        let mut tick = (min / step).ceil() * step;
+        while tick <= max + step * 1e-9 {
+            // -0 looks strange on an axis
+            ticks.push(if tick.abs() < step * 1e-9 { 0f64 } else { tick });
+            tick += step;
+        }
+        ticks
+    }
+    fn label(&self, value: f64) -> String {
+        if has_pre_unit(&self.unit) {
+            format_value(value, &self.unit)
+        } else {
+            // four significant digits without a pre unit
+            let digits = if value != 0f64 {
+                (3 - value.abs().log10().floor() as i32).max(0) as usize
+            } else {
+                0
+            };
+            let mut number = format!("{:.*}", digits, value);
+            if number.contains('.') {
+                number = number.trim_end_matches('0').trim_end_matches('.').to_string();
+            }
+            format!("{} {}", number, self.unit)
+        }
+    }
+}
+
+// one line in the plot
+#[derive(Clone, Debug)]
+struct Series {
+    label: String,
+    points: Vec<(f64, f64)>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Plot {
+    sweep: Sweep,
+    ascii: bool,
+    file: Option<String>, // the svg file
+    log_x: bool,
+    log_y: bool,
+    width: usize, // of the ascii chart in characters
+    height: usize,
+}
+impl Plot {
+    pub fn new(s: Vec<String>, calculation: Calculation) -> Result<Plot, &'static str> {
+        let mut sweep_given: Vec<String> = Vec::new();
+        let mut plot_kind = "ascii".to_string();
+        let mut file: Option<String> = None;
+        let mut log = "".to_string();
+        let mut width = 60usize;
+        let mut height = 20usize;
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "plot" => plot_kind = value.to_lowercase(),
+                "file" => file = Some(value),
+                "log" => log = value.to_lowercase(),
+                "width" => {
+                    width = match value.parse::<usize>() {
+                        Ok(width) if width >= 20 => width,
+                        _ => return Err("the width has to be at least 20"),
+                    }
+                }
+                "height" => {
+                    height = match value.parse::<usize>() {
+                        Ok(height) if height >= 5 => height,
+                        _ => return Err("the height has to be at least 5"),
+                    }
+                }
+                _ => sweep_given.push(i.clone()),
+            }
+        }
+        let (ascii, svg) = match plot_kind.as_str() {
+            "ascii" => (true, file.is_some()),
+            "svg" => (false, true),
+            "both" => (true, true),
+            _ => return Err("give me ascii, svg or both for the plot"),
+        };
+        Ok(Plot {
+            sweep: Sweep::new(sweep_given, calculation)?,
+            ascii,
+            file: if svg {
+                Some(file.unwrap_or_else(|| "plot.svg".to_string()))
+            } else {
+                None
+            },
+            log_x: log.contains('x'),
+            log_y: log.contains('y'),
+            width,
+            height,
+        })
+    }
+    // the lines from the rows of the sweep, a second swept variable gives one line per value
+    fn series(&self) -> Result<Vec<Series>, &'static str> {
+        let mut series: Vec<Series> = Vec::new();
+        let unit = if self.sweep.variables.len() == 2 {
+            pretty_unit(&self.sweep.variables[1].unit).to_string()
+        } else {
+            "".to_string()
+        };
+        for row in self.sweep.rows.iter() {
+            let x = if let Some(x) = numeric(&row.values[0]) {
+                x
+            } else {
+                return Err("the first sweep has to be numbers for the x axis");
+            };
+            let label = if row.values.len() == 2 {
+                if let Some(value) = numeric(&row.values[1]) {
+                    format!("{} = {}", self.sweep.variables[1].name, format_value(value, &unit))
+                } else {
+                    row.values[1].raw.clone()
+                }
+            } else {
+                "".to_string()
+            };
+            // points without result or below zero on a log axis are left out
+            let point = match row.result {
+                Some(y) if (!self.log_x || x > 0f64) && (!self.log_y || y > 0f64) => (x, y),
+                _ => continue,
+            };
+            if let Some(position) = series.iter().position(|series| series.label == label) {
+                series[position].points.push(point);
+            } else {
+                series.push(Series {
+                    label,
+                    points: vec![point],
+                });
+            }
+        }
+        if series.is_empty() {
+            return Err("there is nothing to plot");
+        }
+        Ok(series)
+    }
+    fn axes(&self, series: &[Series]) -> (Axis, Axis) {
+        let variable = &self.sweep.variables[0];
+        let x_unit = pretty_unit(&variable.unit).to_string();
+        let (y_name, y_unit) = self
+            .sweep
+            .calculation
+            .result_name(self.sweep.result_index.unwrap_or_default());
+        let mut x = Axis {
+            title: format!("{} in {}", variable.name, x_unit),
+            unit: x_unit,
+            log: self.log_x,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        };
+        let mut y = Axis {
+            title: format!("{} in {}", y_name, y_unit),
+            unit: y_unit.to_string(),
+            log: self.log_y,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        };
+        for line in series.iter() {
+            for &(px, py) in line.points.iter() {
+                x.min = x.min.min(x.scale(px));
+                x.max = x.max.max(x.scale(px));
+                y.min = y.min.min(y.scale(py));
+                y.max = y.max.max(y.scale(py));
+            }
+        }
+        // a single value gets some room around it
+        for axis in [&mut x, &mut y].iter_mut() {
+            if axis.max - axis.min < 1e-12 * axis.max.abs().max(1f64) {
+                let room = if axis.log { 0.5f64 } else { axis.max.abs().max(1f64) * 0.1f64 };
+                axis.min -= room;
+                axis.max += room;
+            }
+        }
+        (x, y)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        self.sweep.calc(false)?;
+        let series = self.series()?;
+        let (x, y) = self.axes(&series);
+        if should_print && self.ascii {
+            self.print_ascii(&series, &x, &y);
+        }
+        if let Some(ref file) = self.file {
+            let mut svg_file = match File::create(file) {
+                Ok(svg_file) => svg_file,
+                Err(_) => return Err("svg file not writeable"),
+            };
+            if svg_file.write_all(self.svg(&series, &x, &y).as_bytes()).is_err() {
+                return Err("svg file not writeable");
+            }
+            if should_print {
+                println!("The plot is written to: {}", file);
+            }
+        }
+        Ok(())
+    }
+    fn print_ascii(&self, series: &[Series], x: &Axis, y: &Axis) {
+        let (width, height) = (self.width, self.height);
+        let column = |value: f64| (x.position(value) * (width - 1) as f64).round() as usize;
+        let row = |value: f64| height - 1 - (y.position(value) * (height - 1) as f64).round() as usize;
+        let mut grid = vec![vec![' '; width]; height];
+        for (n, line) in series.iter().enumerate() {
+            let marker = MARKERS[n % MARKERS.len()];
+            // the lines between the points first, so the markers stay on top
+            for pair in line.points.windows(2) {
+                let (c0, c1) = (column(pair[0].0), column(pair[1].0));
+                for c in c0.min(c1)..=c0.max(c1) {
+                    let t = if c1 != c0 {
+                        (c as f64 - c0 as f64) / (c1 as f64 - c0 as f64)
+                    } else {
+                        0f64
+                    };
+                    let position = y.position(pair[0].1) * (1f64 - t) + y.position(pair[1].1) * t;
+                    let r = height - 1 - (position * (height - 1) as f64).round() as usize;
+                    if let Some(cell) = grid[r].get_mut(c).filter(|cell| **cell == ' ') {
+                        *cell = '.';
+                    }
+                }
+            }
+            for &(px, py) in line.points.iter() {
+                grid[row(py)][column(px)] = marker;
+            }
+        }
+        let mut labels: Vec<String> = vec!["".to_string(); height];
+        for tick in y.ticks().iter() {
+            labels[row(*tick)] = y.label(*tick);
+        }
+        let label_width = labels.iter().map(|label| label.chars().count()).max().unwrap_or(0);
+        println!("{}", y.title);
+        for (r, line) in grid.iter().enumerate() {
+            let axis = if !labels[r].is_empty() { '+' } else { '|' };
+            let line: String = line.iter().collect();
+            println!("{:>3$} {}{}", labels[r], axis, line, label_width);
+        }
+        // the x axis with the ticks and their labels below, a label is left out if it would
+        // overlap with the one before
+        let mut axis_line: Vec<char> = vec!['-'; width];
+        let mut label_line: Vec<char> = vec![' '; width + 12];
+        let mut free_from = 0usize;
+        for tick in x.ticks().iter() {
+            let c = column(*tick);
+            axis_line[c] = '+';
+            let label: Vec<char> = x.label(*tick).chars().collect();
+            let start = c.saturating_sub(label.len() / 2);
+            if start >= free_from && start + label.len() <= label_line.len() {
+                for (k, character) in label.iter().enumerate() {
+                    label_line[start + k] = *character;
+                }
+                free_from = start + label.len() + 1;
+            }
+        }
+        let axis_line: String = axis_line.iter().collect();
+        let label_line: String = label_line.iter().collect();
+        println!("{:>2$} +{}", "", axis_line, label_width);
+        println!("{:>2$}  {}", "", label_line.trim_end(), label_width);
+        println!("{:>2$}  {:^3$}", "", x.title, label_width, width);
+        if series.len() > 1 {
+            for (n, line) in series.iter().enumerate() {
+                println!("{} {}", MARKERS[n % MARKERS.len()], line.label);
+            }
+        }
+    }
+    // a standalone svg with the axes, a light grid, the lines and a legend
+    fn svg(&self, series: &[Series], x: &Axis, y: &Axis) -> String {
+        let escape = |s: &str| {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+        };
+        let (width, height) = (720f64, 440f64);
+        let (left, right, top, bottom) = (90f64, 180f64, 30f64, 70f64);
+        let plot_width = width - left - right;
+        let plot_height = height - top - bottom;
+        let sx = |value: f64| left + x.position(value) * plot_width;
+        let sy = |value: f64| top + (1f64 - y.position(value)) * plot_height;
+        let mut svg = String::new();
+        svg.push_str(&format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" font-family=\"sans-serif\" font-size=\"12\">\n",
+            width, height, width, height
+        ));
+        svg.push_str(&format!(
+            "<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n",
+            width, height
+        ));
+        for tick in x.ticks().iter() {
+            let px = sx(*tick);
+            svg.push_str(&format!(
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#dddddd\"/>\n",
+                px, top, px, top + plot_height
+            ));
+            svg.push_str(&format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>\n",
+                px,
+                top + plot_height + 18f64,
+                escape(&x.label(*tick))
+            ));
+        }
+        for tick in y.ticks().iter() {
+            let py = sy(*tick);
+            svg.push_str(&format!(
+                "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#dddddd\"/>\n",
+                left, py, left + plot_width, py
+            ));
+            svg.push_str(&format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\n",
+                left - 6f64,
+                py + 4f64,
+                escape(&y.label(*tick))
+            ));
+        }
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"black\"/>\n",
+            left, top, plot_width, plot_height
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>\n",
+            left + plot_width / 2f64,
+            height - 20f64,
+            escape(&x.title)
+        ));
+        svg.push_str(&format!(
+            "<text x=\"20\" y=\"{:.1}\" text-anchor=\"middle\" transform=\"rotate(-90 20 {:.1})\">{}</text>\n",
+            top + plot_height / 2f64,
+            top + plot_height / 2f64,
+            escape(&y.title)
+        ));
+        for (n, line) in series.iter().enumerate() {
+            let colour = COLOURS[n % COLOURS.len()];
+            let points: Vec<String> = line
+                .points
+                .iter()
+                .map(|&(px, py)| format!("{:.1},{:.1}", sx(px), sy(py)))
+                .collect();
+            svg.push_str(&format!(
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\"/>\n",
+                points.join(" "),
+                colour
+            ));
+            for &(px, py) in line.points.iter() {
+                svg.push_str(&format!(
+                    "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"2.5\" fill=\"{}\"/>\n",
+                    sx(px),
+                    sy(py),
+                    colour
+                ));
+            }
+            if !line.label.is_empty() {
+                let ly = top + 10f64 + n as f64 * 18f64;
+                svg.push_str(&format!(
+                    "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" stroke-width=\"2\"/>\n",
+                    left + plot_width + 15f64,
+                    ly,
+                    left + plot_width + 35f64,
+                    ly,
+                    colour
+                ));
+                svg.push_str(&format!(
+                    "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>\n",
+                    left + plot_width + 40f64,
+                    ly + 4f64,
+                    escape(&line.label)
+                ));
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::strings;
+
+    fn axis(log: bool, min: f64, max: f64) -> Axis {
+        Axis {
+            title: "".to_string(),
+            unit: "V".to_string(),
+            log,
+            min,
+            max,
+        }
+    }
+
+    fn plot(given: &[&str]) -> Plot {
+        let mut plot = Plot::new(strings(given), Calculation::Uri).unwrap();
+        plot.sweep.calc(false).unwrap();
+        plot
+    }
+
+    #[test]
+    fn ticks() {
+        assert_eq!(axis(false, 0f64, 10f64).ticks(), [0f64, 2f64, 4f64, 6f64, 8f64, 10f64]);
+        assert_eq!(axis(true, 0f64, 3f64).ticks(), [1f64, 10f64, 100f64, 1000f64]);
+        assert_eq!(axis(false, -1f64, 1f64).ticks()[2], 0f64);
+        assert!((axis(true, 0f64, 3f64).position(100f64) - 2f64 / 3f64).abs() < 1e-12);
+        assert_eq!(axis(false, 0f64, 1f64).label(2200f64), "2.2 kV");
+    }
+
+    #[test]
+    fn one_series_per_value() {
+        let plot = plot(&["u=1V..10V:1V", "r={10ohm,20ohm}"]);
+        let series = plot.series().unwrap();
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].points.len(), 10);
+        assert_eq!(series[1].label, "r = 20 Ω");
+        let (x, y) = plot.axes(&series);
+        assert_eq!((x.min, x.max), (1f64, 10f64));
+        assert!((y.max - 1f64).abs() < 1e-12);
+        let svg = plot.svg(&series, &x, &y);
+        assert!(svg.starts_with("<svg") && svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn log_axis_leaves_out_zero() {
+        let plot = plot(&["u=0V..10V:5V", "r=10ohm", "log=xy"]);
+        assert_eq!(plot.series().unwrap()[0].points.len(), 2);
+        assert!(Plot::new(vec!["plot=png".to_string()], Calculation::Uri).is_err());
+    }
+}
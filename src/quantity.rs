@@ -0,0 +1,371 @@
+// a value with its dimension. everything is kept in the si base units, so 1.5mm2 is 1.5e-6 m²
+// and 4.7kohm is 4700 kg·m²·s⁻³·A⁻². with the dimension we can check that only fitting values
+// are added and that a result fits to the place it goes to (like a length for Elect)
+use std::f64;
+use std::fmt;
+use std::ops::Neg;
+
+use super::PreUnits;
+use convert::significant;
+
+// the exponents of the base units m, kg, s, A and K
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dimension(pub [i8; 5]);
+
+static BASE_UNITS: [&str; 5] = ["m", "kg", "s", "A", "K"];
+
+pub const DIMENSIONLESS: Dimension = Dimension([0, 0, 0, 0, 0]);
+
+// the exponents are small numbers, (1V^20)^20 would run over
+const TOO_LARGE: &str = "the exponent of the unit is too large";
+
+// symbol, factor to the si base units, dimension and if it can have a pre unit
+static UNITS: [(&str, f64, [i8; 5], bool); 48] = [
+    ("m", 1f64, [1, 0, 0, 0, 0], true),
+    ("g", 1e-3f64, [0, 1, 0, 0, 0], true),
+    ("s", 1f64, [0, 0, 1, 0, 0], true),
+    ("A", 1f64, [0, 0, 0, 1, 0], true),
+    ("K", 1f64, [0, 0, 0, 0, 1], true),
+    ("V", 1f64, [2, 1, -3, -1, 0], true),
+    ("ohm", 1f64, [2, 1, -3, -2, 0], true),
+    ("Ω", 1f64, [2, 1, -3, -2, 0], true),
+    ("W", 1f64, [2, 1, -3, 0, 0], true),
+    ("VA", 1f64, [2, 1, -3, 0, 0], true),
+    ("var", 1f64, [2, 1, -3, 0, 0], true),
+    ("J", 1f64, [2, 1, -2, 0, 0], true),
+    ("C", 1f64, [0, 0, 1, 1, 0], true),
+    ("F", 1f64, [-2, -1, 4, 2, 0], true),
+    ("H", 1f64, [2, 1, -2, -2, 0], true),
+    ("Hz", 1f64, [0, 0, -1, 0, 0], true),
+    ("S", 1f64, [-2, -1, 3, 2, 0], true),
+    ("T", 1f64, [0, 1, -2, -1, 0], true),
+    ("Wb", 1f64, [2, 1, -2, -1, 0], true),
+    ("N", 1f64, [1, 1, -2, 0, 0], true),
+    ("min", 60f64, [0, 0, 1, 0, 0], false),
+    ("h", 3600f64, [0, 0, 1, 0, 0], false),
+    ("%", 1e-2f64, [0, 0, 0, 0, 0], false),
+    // the spellings Elect uses
+    ("mm2", 1e-6f64, [2, 0, 0, 0, 0], false),
+    ("ohmxmm2", 1e-6f64, [4, 1, -3, -2, 0], false),
+    ("ohmxmm2/m", 1e-6f64, [3, 1, -3, -2, 0], false),
+    ("rpm", 1f64 / 60f64, [0, 0, -1, 0, 0], false),
+    // imperial lengths and the circular mil of the wire areas (kcmil or MCM)
+    ("in", 0.0254f64, [1, 0, 0, 0, 0], false),
+    ("ft", 0.3048f64, [1, 0, 0, 0, 0], false),
+    ("yd", 0.9144f64, [1, 0, 0, 0, 0], false),
+    ("mi", 1609.344f64, [1, 0, 0, 0, 0], false),
+    ("mil", 25.4e-6f64, [1, 0, 0, 0, 0], false),
+    ("thou", 25.4e-6f64, [1, 0, 0, 0, 0], false),
+    ("cmil", f64::consts::PI / 4f64 * 25.4e-6f64 * 25.4e-6f64, [2, 0, 0, 0, 0], true),
+    ("MCM", 1000f64 * f64::consts::PI / 4f64 * 25.4e-6f64 * 25.4e-6f64, [2, 0, 0, 0, 0], false),
+    // energy, charge and power
+    ("Wh", 3600f64, [2, 1, -2, 0, 0], true),
+    ("Ah", 3600f64, [0, 0, 1, 1, 0], true),
+    ("eV", 1.602176634e-19f64, [2, 1, -2, 0, 0], true),
+    ("cal", 4.184f64, [2, 1, -2, 0, 0], true),
+    ("BTU", 1055.05585f64, [2, 1, -2, 0, 0], false),
+    ("hp", 745.69987f64, [2, 1, -3, 0, 0], false),
+    // the magnetic units of the cgs system and the ampere turns
+    ("Oe", 1000f64 / (4f64 * f64::consts::PI), [-1, 0, 0, 1, 0], false),
+    ("G", 1e-4f64, [0, 1, -2, -1, 0], false),
+    ("Mx", 1e-8f64, [2, 1, -2, -1, 0], false),
+    ("Gb", 10f64 / (4f64 * f64::consts::PI), [0, 0, 0, 1, 0], false),
+    ("At", 1f64, [0, 0, 0, 1, 0], true),
+    // the conductivity of annealed copper, used as %IACS
+    ("IACS", 5.8001e7f64, [-3, -1, 3, 2, 0], false),
+    ("%IACS", 5.8001e5f64, [-3, -1, 3, 2, 0], false),
+];
+
+// the units a result is shown in, the first one with the dimension is taken
+static NAMED_UNITS: [&str; 14] = [
+    "V", "A", "ohm", "W", "J", "C", "F", "H", "Hz", "S", "T", "Wb", "N", "s",
+];
+
+// the units of the constants and the specific values, which have no symbol of their own
+static DERIVED_UNITS: [(&str, [i8; 5]); 6] = [
+    ("F/m", [-3, -1, 4, 2, 0]),
+    ("H/m", [1, 1, -2, -2, 0]),
+    ("Ω·m", [3, 1, -3, -2, 0]),
+    ("S/m", [-3, -1, 3, 2, 0]),
+    ("V/m", [1, 1, -3, -1, 0]),
+    ("A/m", [-1, 0, 0, 1, 0]),
+];
+
+impl Dimension {
+    fn combine(&self, other: &Dimension, sign: i8) -> Result<Dimension, &'static str> {
+        let mut exponents = self.0;
+        for (exponent, other) in exponents.iter_mut().zip(other.0.iter()) {
+            *exponent = other
+                .checked_mul(sign)
+                .and_then(|other| exponent.checked_add(other))
+                .ok_or(TOO_LARGE)?;
+        }
+        Ok(Dimension(exponents))
+    }
+    fn times(&self, n: i8) -> Result<Dimension, &'static str> {
+        let mut exponents = self.0;
+        for exponent in exponents.iter_mut() {
+            *exponent = exponent.checked_mul(n).ok_or(TOO_LARGE)?;
+        }
+        Ok(Dimension(exponents))
+    }
+}
+impl fmt::Display for Dimension {
+    // like kg*m^2/(s^3*A), so it can be read again as expression
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let part = |k: usize, exponent: i8| {
+            if exponent == 1 {
+                BASE_UNITS[k].to_string()
+            } else {
+                format!("{}^{}", BASE_UNITS[k], exponent)
+            }
+        };
+        // kg first, it reads better
+        let order = [1, 0, 2, 3, 4];
+        let numerator: Vec<String> = order
+            .iter()
+            .filter(|k| self.0[**k] > 0)
+            .map(|k| part(*k, self.0[*k]))
+            .collect();
+        let denominator: Vec<String> = order
+            .iter()
+            .filter(|k| self.0[**k] < 0)
+            .map(|k| part(*k, -self.0[*k]))
+            .collect();
+        let numerator = if numerator.is_empty() {
+            "1".to_string()
+        } else {
+            numerator.join("*")
+        };
+        match denominator.len() {
+            0 => write!(f, "{}", numerator),
+            1 => write!(f, "{}/{}", numerator, denominator[0]),
+            _ => write!(f, "{}/({})", numerator, denominator.join("*")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quantity {
+    pub value: f64, // in the si base units
+    pub dimension: Dimension,
+}
+impl Quantity {
+    pub fn new(value: f64, dimension: Dimension) -> Quantity {
+        Quantity {
+            value,
+            dimension,
+        }
+    }
+    pub fn number(value: f64) -> Quantity {
+        Quantity::new(value, DIMENSIONLESS)
+    }
+    // a unit like kohm or mm2 as quantity, the pre unit is only looked at if the whole symbol
+    // isn't a unit (so mm is milli metre, but min is minutes)
+    pub fn unit(symbol: &str) -> Option<Quantity> {
+        if let Some(unit) = UNITS.iter().find(|unit| unit.0 == symbol) {
+            return Some(Quantity::new(unit.1, Dimension(unit.2)));
+        }
+        // m2, cm2 or in3 are the square and the cube of the unit
+        for (power, n) in [('2', 2i8), ('3', 3i8)].iter() {
+            if let Some(base) = symbol.strip_suffix(*power) {
+                if let Some(unit) = Quantity::unit(base) {
+                    let dimension = unit.dimension.times(*n).ok()?;
+                    return Some(Quantity::new(unit.value.powi(*n as i32), dimension));
+                }
+            }
+        }
+        let mut chars = symbol.chars();
+        let pre_unit = chars.next()?;
+        let rest = chars.as_str();
+        let pre_unit = match pre_unit {
+            'P' | 'T' | 'G' | 'M' | 'k' | 'm' | 'n' | 'p' => pre_unit.to_string(),
+            'µ' | 'μ' | 'u' => "µ".to_string(),
+            // centi is z in PreUnits
+            'c' => "z".to_string(),
+            _ => return None,
+        };
+        UNITS
+            .iter()
+            .find(|unit| unit.0 == rest && unit.3)
+            .map(|unit| {
+                Quantity::new(PreUnits::new(pre_unit).to_base(unit.1), Dimension(unit.2))
+            })
+    }
+    pub fn is_dimensionless(&self) -> bool {
+        self.dimension == DIMENSIONLESS
+    }
+    pub fn plus(&self, other: &Quantity) -> Result<Quantity, &'static str> {
+        if self.dimension != other.dimension {
+            return Err("only values with the same unit can be added");
+        }
+        Ok(Quantity::new(self.value + other.value, self.dimension))
+    }
+    pub fn minus(&self, other: &Quantity) -> Result<Quantity, &'static str> {
+        self.plus(&-*other)
+    }
+    pub fn times(&self, other: &Quantity) -> Result<Quantity, &'static str> {
+        Ok(Quantity::new(
+            self.value * other.value,
+            self.dimension.combine(&other.dimension, 1)?,
+        ))
+    }
+    pub fn divided(&self, other: &Quantity) -> Result<Quantity, &'static str> {
+        Ok(Quantity::new(
+            self.value / other.value,
+            self.dimension.combine(&other.dimension, -1)?,
+        ))
+    }
+    // the power with an exponent without unit. with a unit the exponent has to be whole
+    pub fn pow(&self, exponent: &Quantity) -> Result<Quantity, &'static str> {
+        if !exponent.is_dimensionless() {
+            return Err("an exponent can't have a unit");
+        }
+        let n = exponent.value;
+        if self.is_dimensionless() {
+            return Ok(Quantity::number(self.value.powf(n)));
+        }
+        if n.fract() != 0f64 || n.abs() > 20f64 {
+            return Err("a value with a unit can only have a whole exponent");
+        }
+        Ok(Quantity::new(
+            self.value.powi(n as i32),
+            self.dimension.times(n as i8)?,
+        ))
+    }
+    // the n-th root, every exponent of the unit has to be divisible by n
+    pub fn root(&self, n: i8) -> Result<Quantity, &'static str> {
+        if self.dimension.0.iter().any(|exponent| exponent % n != 0) {
+            return Err("the root of this unit isn't a unit");
+        }
+        let mut exponents = self.dimension.0;
+        for exponent in exponents.iter_mut() {
+            *exponent /= n;
+        }
+        Ok(Quantity::new(
+            self.value.powf(1f64 / n as f64),
+            Dimension(exponents),
+        ))
+    }
+    // the number in the given unit, like 1.5 for 1.5e-6 m² in mm2
+    pub fn in_unit(&self, unit: &Quantity) -> Result<f64, &'static str> {
+        if self.dimension != unit.dimension {
+            return Err("the unit doesn't fit");
+        }
+        Ok(self.value / unit.value)
+    }
+    // the name of the unit if there is one for the dimension
+    fn named_unit(&self) -> Option<&'static str> {
+        if self.dimension.0 == [1, 0, 0, 0, 0] {
+            return Some("m");
+        }
+        NAMED_UNITS
+            .iter()
+            .cloned()
+            .find(|name| Quantity::unit(name).map(|unit| unit.dimension) == Some(self.dimension))
+            .or_else(|| {
+                DERIVED_UNITS
+                    .iter()
+                    .find(|unit| Dimension(unit.1) == self.dimension)
+                    .map(|unit| unit.0)
+            })
+    }
+    // for reading, with the pre unit and four digits like 2.553 mA
+    pub fn pretty(&self) -> String {
+        match self.named_unit() {
+            Some(unit) => super::format_value(self.value, if unit == "ohm" { "Ω" } else { unit }),
+            // a number has no pre unit, 500 m would be read as metres
+            None if self.is_dimensionless() => significant(self.value),
+            // areas of wires are better read in mm²
+            None if self.dimension.0 == [2, 0, 0, 0, 0] => {
+                let area = self.value * 1e6f64;
+                let digits = if area != 0f64 {
+                    (3 - area.abs().log10().floor() as i32).max(0) as usize
+                } else {
+                    0
+                };
+                let number = format!("{:.*}", digits, area);
+                format!("{} mm²", number.trim_end_matches('0').trim_end_matches('.'))
+            }
+            None => format!("{} {}", significant(self.value), self.dimension),
+        }
+    }
+}
+impl fmt::Display for Quantity {
+    // the whole number with the si unit, like 0.0025531914893617A, so it can be given again
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.named_unit() {
+            Some(unit) => write!(f, "{}{}", self.value, unit),
+            None if self.is_dimensionless() => write!(f, "{}", self.value),
+            None => write!(f, "{}{}", self.value, self.dimension),
+        }
+    }
+}
+impl Neg for Quantity {
+    type Output = Quantity;
+    fn neg(self) -> Quantity {
+        Quantity::new(-self.value, self.dimension)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(symbol: &str) -> Quantity {
+        Quantity::unit(symbol).unwrap()
+    }
+
+    #[test]
+    fn symbols_before_pre_units() {
+        // min is minutes and mil a thousandth inch, not milli in and milli il
+        assert_eq!(unit("min"), Quantity::new(60f64, Dimension([0, 0, 1, 0, 0])));
+        assert_eq!(unit("mil").value, 25.4e-6f64);
+        assert_eq!(unit("mm2"), Quantity::new(1e-6f64, Dimension([2, 0, 0, 0, 0])));
+        assert_eq!(unit("Mx").value, 1e-8f64);
+        // kcmil is a pre unit on cmil, MCM can't have one
+        assert!((unit("kcmil").value / unit("MCM").value - 1f64).abs() < 1e-12);
+        assert!(Quantity::unit("kMCM").is_none());
+        assert_eq!(unit("mm").value, 1e-3f64);
+        assert!((unit("cm2").value - 1e-4f64).abs() < 1e-18);
+        assert_eq!(unit("µF"), Quantity::unit("uF").unwrap());
+        assert!(Quantity::unit("xm").is_none());
+    }
+
+    #[test]
+    fn pow_and_root() {
+        let area = unit("m").pow(&Quantity::number(2f64)).unwrap();
+        assert_eq!(area.dimension, Dimension([2, 0, 0, 0, 0]));
+        let side = Quantity::new(4f64, area.dimension).root(2).unwrap();
+        assert_eq!(side, Quantity::new(2f64, Dimension([1, 0, 0, 0, 0])));
+        assert!(area.root(3).is_err());
+        assert!(unit("m").pow(&Quantity::number(0.5f64)).is_err());
+        assert!(unit("m").pow(&unit("m")).is_err());
+        let two = Quantity::number(4f64).pow(&Quantity::number(0.5f64));
+        assert_eq!(two, Ok(Quantity::number(2f64)));
+        let ohm = unit("V").divided(&unit("A")).unwrap();
+        assert_eq!(ohm.dimension, unit("ohm").dimension);
+        assert!(unit("V").plus(&unit("A")).is_err());
+    }
+
+    #[test]
+    fn exponents_too_large() {
+        let large = unit("V").pow(&Quantity::number(20f64)).unwrap();
+        assert_eq!(large.pow(&Quantity::number(20f64)), Err(TOO_LARGE));
+        // m^126 is the largest power of m2
+        let square = |n: usize| {
+            (0..n).try_fold(Quantity::number(1f64), |product, _| product.times(&unit("m2")))
+        };
+        assert!(square(63).is_ok());
+        assert_eq!(square(64), Err(TOO_LARGE));
+    }
+
+    #[test]
+    fn pretty_units() {
+        assert_eq!(unit("kohm").pretty(), "1 kΩ");
+        assert_eq!(unit("mm2").pretty(), "1 mm²");
+        assert_eq!(Quantity::number(0.5f64).pretty(), "0.5");
+        assert_eq!(unit("m").times(&unit("s")).unwrap().pretty(), "1 m*s");
+        assert_eq!(format!("{}", unit("mA")), "0.001A");
+    }
+}
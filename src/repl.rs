@@ -0,0 +1,504 @@
+// the interactive mode. values are assigned to variables (l = 25m, mat = copper), a variable is
+// solved with the others (R? or solve R) and every result is kept as ans, $1, $2, ... the
+// subcommands of the command line work here too and can use the variables and results:
+//   > u = 12V
+//   > r = 4k7
+//   > I?
+//   $1: I = 2.553 mA
+//   > led u=$u i=ans colour=red
+// the history is kept in ~/.elek_wider_history, a session can be saved and loaded again
+use std::env;
+use std::fs::File;
+use std::io::{Read, Write};
+
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{CompletionType, Config, Context, Editor, Helper};
+
+use super::{parse_value, AsciiFloat, MATERIAL_NAMES};
+use cli;
+use expression;
+use resistivity;
+
+// the commands of the interactive mode, the subcommands of the command line come on top
+static COMMANDS: [&str; 9] = [
+    "help", "vars", "solve", "history", "save", "load", "clear", "quit", "exit",
+];
+
+// the units for the completion after a number
+static UNITS: [&str; 22] = [
+    "V", "A", "ohm", "Ω", "m", "mm", "mm2", "ohmxmm2/m", "°C", "F", "H", "Hz", "W", "VA", "var",
+    "s", "%", "Wh", "AWG", "cmil", "dBm", "dBW",
+];
+
+// the units of the subcommands which aren't completed, a single value has one of them or of UNITS
+static OTHER_UNITS: [&str; 16] = [
+    "ohm/km", "Ω/km", "Ω·mm²/m", "mm²", "A/mm2", "A/mm²", "MS/m", "T", "Wb", "At", "A/Wb", "1/H",
+    "K", "ppm/K", "degC", "oz",
+];
+
+// the quantities solve knows, for Ohm's law and for the wire
+static URI_NAMES: [&str; 3] = ["u", "i", "r"];
+static ELECT_NAMES: [&str; 15] = [
+    "r", "rho", "ρ", "sigma", "σ", "rs", "h", "l", "a", "d", "t", "s", "er", "mat", "material",
+];
+
+// the completion of commands, variables, materials and units
+struct Completion {
+    words: Vec<String>,
+}
+impl Completer for Completion {
+    type Candidate = String;
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> Result<(usize, Vec<String>), ReadlineError> {
+        let start = line[..pos]
+            .rfind([' ', '=', ';', '"'])
+            .map_or(0, |k| k + 1);
+        let word = &line[start..pos];
+        if word.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+            // after a number only the unit is completed, with or without a pre unit
+            let unit_start = word.find(|c: char| !c.is_ascii_float()).unwrap_or(word.len());
+            let partial = &word[unit_start..];
+            let mut candidates: Vec<String> = Vec::new();
+            for unit in UNITS.iter() {
+                if unit.starts_with(partial) {
+                    candidates.push(unit.to_string());
+                }
+                let mut chars = partial.chars();
+                if let Some(pre_unit) = chars.next() {
+                    if "PTGMkmuµnp".contains(pre_unit)
+                        && unit.starts_with(chars.as_str())
+                        && *unit != "%"
+                    {
+                        candidates.push(format!("{}{}", pre_unit, unit));
+                    }
+                }
+            }
+            candidates.sort();
+            candidates.dedup();
+            return Ok((start + unit_start, candidates));
+        }
+        let candidates = self
+            .words
+            .iter()
+            .filter(|candidate| candidate.starts_with(word))
+            .cloned()
+            .collect();
+        Ok((start, candidates))
+    }
+}
+impl Hinter for Completion {
+    type Hint = String;
+}
+impl Highlighter for Completion {}
+impl Validator for Completion {}
+impl Helper for Completion {}
+
+// the variables and results of one session
+#[derive(Clone, Debug, Default)]
+pub struct Session {
+    variables: Vec<(String, String)>,
+    results: Vec<String>,
+}
+impl Session {
+    pub fn new() -> Session {
+        Default::default()
+    }
+    // the value of a variable, ans or $n
+    fn lookup(&self, name: &str) -> Option<String> {
+        let name = name.trim().to_lowercase();
+        if name == "ans" {
+            return self.results.last().cloned();
+        }
+        if let Some(number) = name.strip_prefix('$') {
+            if let Ok(number) = number.parse::<usize>() {
+                if number >= 1 && number <= self.results.len() {
+                    return Some(self.results[number - 1].clone());
+                }
+                return None;
+            }
+            return self.lookup(number);
+        }
+        self.variables
+            .iter()
+            .find(|variable| variable.0 == name)
+            .map(|variable| variable.1.clone())
+    }
+    // a given value with the variables and results put in, also after name=
+    fn substitute(&self, token: &str) -> String {
+        if let Some(value) = self.lookup(token) {
+            return value;
+        }
+        if let Some(position) = token.find('=') {
+            if let Some(value) = self.lookup(&token[position + 1..]) {
+                return format!("{}={}", &token[..position], value);
+            }
+        }
+        token.to_string()
+    }
+    pub fn assign(&mut self, name: &str, value: &str) -> Result<(), &'static str> {
+        let name = name.trim().to_lowercase();
+        let valid = name.starts_with(|c: char| c.is_alphabetic())
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if !valid || name == "ans" {
+            return Err("a variable name starts with a letter and has only letters and digits");
+        }
+        let value = value.trim();
+        if value.is_empty() {
+            return Err("give me a value for the variable");
+        }
+        // an expression like 2*l + 3m is calculated, a single value like 4k7 or copper is kept.
+        // the sheet resistance has a slash too, but it is no expression
+        let value = if resistivity::parse_sheet(value).is_none() && expression::is_expression(value)
+        {
+            let result = expression::evaluate(value, &|name| self.lookup(name))?;
+            if !result.value.is_finite() {
+                return Err("the value is too large");
+            }
+            println!("{} = {}", name, result.pretty());
+            result.to_string()
+        } else {
+            let value = self.substitute(value);
+            check_value(&value)?;
+            value
+        };
+        if let Some(position) = self.variables.iter().position(|variable| variable.0 == name) {
+            self.variables[position].1 = value;
+        } else {
+            self.variables.push((name, value));
+        }
+        Ok(())
+    }
+    // solves the searched variable with the assigned ones. R belongs to Ohm's law if U and I are
+    // there, otherwise to the wire
+    pub fn solve(&mut self, searched: &str) -> Result<(), &'static str> {
+        let searched = searched.trim().to_lowercase();
+        let assigned = |name: &str| self.variables.iter().any(|variable| variable.0 == name);
+        let names: &[&str] = match searched.as_str() {
+            "u" | "i" => &URI_NAMES,
+            "r" if assigned("u") && assigned("i") => &URI_NAMES,
+            _ => &ELECT_NAMES,
+        };
+        let given: Vec<String> = self
+            .variables
+            .iter()
+            .filter(|variable| variable.0 != searched && names.contains(&variable.0.as_str()))
+            .map(|variable| {
+                let name = if variable.0 == "mat" { "material" } else { variable.0.as_str() };
+                format!("{}={}", name, variable.1)
+            })
+            .collect();
+        let solution = cli::solve(&given.join(";"), &searched)?;
+        self.results.push(solution.given());
+        println!("${}: {}", self.results.len(), solution);
+        Ok(())
+    }
+    pub fn save(&self, file: &str) -> Result<(), &'static str> {
+        let mut text = String::from("# elek_wider session\n");
+        for variable in self.variables.iter() {
+            text.push_str(&format!("{} = {}\n", variable.0, variable.1));
+        }
+        for (k, result) in self.results.iter().enumerate() {
+            text.push_str(&format!("${} = {}\n", k + 1, result));
+        }
+        let mut session_file = match File::create(file) {
+            Ok(session_file) => session_file,
+            Err(_) => return Err("session file not writeable"),
+        };
+        if session_file.write_all(text.as_bytes()).is_err() {
+            return Err("session file not writeable");
+        }
+        Ok(())
+    }
+    // the variables of the file are added to the ones we have, the results come after ours
+    pub fn load(&mut self, file: &str) -> Result<(), &'static str> {
+        let mut text = String::new();
+        match File::open(file) {
+            Ok(mut session_file) => {
+                if session_file.read_to_string(&mut text).is_err() {
+                    return Err("session file not readable");
+                }
+            }
+            Err(_) => return Err("session file not found"),
+        }
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, value) = match line.find('=') {
+                Some(position) => (line[..position].trim(), line[position + 1..].trim()),
+                None => return Err("session file is strange"),
+            };
+            if name.starts_with('$') {
+                self.results.push(value.to_string());
+            } else {
+                self.assign(name, value)?;
+            }
+        }
+        Ok(())
+    }
+    fn print_variables(&self) {
+        for variable in self.variables.iter() {
+            println!("{} = {}", variable.0, variable.1);
+        }
+        for (k, result) in self.results.iter().enumerate() {
+            println!("${} = {}", k + 1, result);
+        }
+    }
+    // the words for the completion
+    fn words(&self) -> Vec<String> {
+        let mut words: Vec<String> = Vec::new();
+        words.extend(COMMANDS.iter().map(|command| command.to_string()));
+        words.extend(cli::command_names().iter().map(|command| command.to_string()));
+        words.extend(MATERIAL_NAMES.iter().map(|material| material.to_string()));
+        words.extend(self.variables.iter().map(|variable| variable.0.clone()));
+        words.push("ans".to_string());
+        words.sort();
+        words.dedup();
+        words
+    }
+    // one line of the interactive mode, everything except quit and history
+    pub fn execute(&mut self, line: &str) -> Result<(), &'static str> {
+        let line = line.trim();
+        let words = split_arguments(line);
+        if words.is_empty() {
+            return Ok(());
+        }
+        match words[0].as_str() {
+            "help" if words.len() == 1 => {
+                print_help();
+                return Ok(());
+            }
+            "vars" => {
+                self.print_variables();
+                return Ok(());
+            }
+            "clear" => {
+                *self = Session::new();
+                return Ok(());
+            }
+            "save" | "load" => {
+                if words.len() != 2 {
+                    return Err("give me the file of the session");
+                }
+                return if words[0] == "save" {
+                    self.save(&words[1])
+                } else {
+                    self.load(&words[1])
+                };
+            }
+            "solve" if words.len() == 2 => return self.solve(&words[1]),
+            _ => {}
+        }
+        if line.ends_with('?') && words.len() == 1 {
+            return self.solve(line.trim_end_matches('?'));
+        }
+        // name = value, but led u=12V is a subcommand
+        if let Some(position) = line.find('=') {
+            let name = line[..position].trim();
+            if !name.contains(' ') && !cli::command_names().contains(&name) {
+                return self.assign(name, &line[position + 1..]);
+            }
+        }
+        if cli::command_names().contains(&words[0].as_str()) {
+            if words[0] == "interactive" {
+                return Err("we are already in the interactive mode");
+            }
+            let args: Vec<String> = words.iter().map(|word| self.substitute(word)).collect();
+            // the subcommand prints its own errors
+            cli::run(args);
+            return Ok(());
+        }
+        if words.len() == 1 {
+            if let Some(value) = self.lookup(line) {
+                println!("{}", value);
+                return Ok(());
+            }
+        }
+        // everything else is calculated, like 60W/230V
+        if expression::is_expression(line) {
+            let result = expression::evaluate(line, &|name| self.lookup(name))?;
+            self.results.push(result.to_string());
+            println!("${}: {}", self.results.len(), result.pretty());
+            return Ok(());
+        }
+        Err("input not understood, try help")
+    }
+}
+
+// splits the line at the spaces, but not inside double quotes
+fn split_arguments(line: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ' ' | '\t' if !quoted => {
+                if !word.is_empty() {
+                    words.push(word.clone());
+                    word.clear();
+                }
+            }
+            _ => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+// a single value is put into the commands as it is typed, so it has to be one word which
+// parse_value reads with one of the units, a form of rho or the sheet resistance, or a material
+fn check_value(value: &str) -> Result<(), &'static str> {
+    if value.contains(char::is_whitespace) {
+        return Err("write the value without spaces, like 3mm");
+    }
+    if MATERIAL_NAMES.contains(&value.to_lowercase().as_str()) {
+        return Ok(());
+    }
+    let rho = resistivity::parse_sheet(value).or_else(|| resistivity::parse_rho(value));
+    let number = match rho {
+        Some(rho) => rho?,
+        None => {
+            let units: Vec<&str> = UNITS.iter().chain(OTHER_UNITS.iter()).cloned().collect();
+            match parse_value(value, &units) {
+                Ok(number) => number,
+                Err(_) => return Err("give me a number with a unit like 4k7 or 25mm or a material"),
+            }
+        }
+    };
+    if !number.is_finite() {
+        return Err("the value is too large");
+    }
+    Ok(())
+}
+
+fn print_help() {
+    println!("name = value      assigns a value to a variable, like l = 25m or mat = copper");
+    println!("R? or solve R     solves R with the variables, the result is kept as ans and $n");
+    println!("name, ans or $n   shows the value");
+    println!("2*l + 3m          calculates with units, like pi*(0.8mm)^2/4 or sqrt(2)*230V");
+    println!("                  functions: sqrt, log, ln, exp, sin, cos, tan, abs, min, max");
+    println!("                  constants: pi, e, mu0, eps0");
+    println!("vars              shows all variables and results");
+    println!("save file         saves the variables and results");
+    println!("load file         loads them again");
+    println!("clear             forgets all variables and results");
+    println!("history           shows the last lines");
+    println!("quit or exit      ends the session");
+    println!("every subcommand of the command line works too, like ohm $u 2A or help led");
+}
+
+fn history_file() -> Option<String> {
+    env::var("HOME")
+        .ok()
+        .map(|home| format!("{}/.elek_wider_history", home))
+}
+
+pub fn run() -> Result<(), &'static str> {
+    // the candidates are listed like in a shell instead of going through them
+    let config = Config::builder()
+        .completion_type(CompletionType::List)
+        .build();
+    let mut editor: Editor<Completion, DefaultHistory> = match Editor::with_config(config) {
+        Ok(editor) => editor,
+        Err(_) => return Err("the terminal is not usable"),
+    };
+    let mut session = Session::new();
+    editor.set_helper(Some(Completion {
+        words: session.words(),
+    }));
+    if let Some(ref file) = history_file() {
+        // there is no history the first time
+        let _ = editor.load_history(file);
+    }
+    println!("elek_wider interactive mode, help shows the commands");
+    loop {
+        let line = match editor.readline("> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(_) => break,
+        };
+        let line = line.trim().to_string();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line.as_str());
+        match line.as_str() {
+            "quit" | "exit" => break,
+            "history" => {
+                for (k, entry) in editor.history().iter().enumerate() {
+                    println!("{:>4} {}", k + 1, entry);
+                }
+            }
+            _ => {
+                if let Err(error) = session.execute(&line) {
+                    println!("error: {}!", error);
+                }
+            }
+        }
+        if let Some(helper) = editor.helper_mut() {
+            helper.words = session.words();
+        }
+    }
+    if let Some(ref file) = history_file() {
+        if editor.save_history(file).is_err() {
+            return Err("history not writeable");
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assign_single_values() {
+        let mut session = Session::new();
+        for (name, value) in [("r", "4k7"), ("l", "25m"), ("mat", "Copper"), ("rs", "0.5mΩ/sq")] {
+            assert!(session.assign(name, value).is_ok(), "{} = {}", name, value);
+        }
+        assert_eq!(session.lookup("R"), Some("4k7".to_string()));
+        // a variable is put in for another one
+        session.assign("d", "l").unwrap();
+        assert_eq!(session.lookup("d"), Some("25m".to_string()));
+    }
+
+    #[test]
+    fn reject_strange_values() {
+        let mut session = Session::new();
+        assert!(session.assign("x", "1e400").is_err());
+        assert!(session.assign("y", "3 mm").is_err());
+        assert!(session.assign("z", "wood").is_err());
+        assert!(session.assign("2x", "1m").is_err());
+        assert!(session.assign("ans", "1m").is_err());
+        assert_eq!(session.lookup("x"), None);
+    }
+
+    #[test]
+    fn assign_expression() {
+        let mut session = Session::new();
+        session.assign("l", "2m").unwrap();
+        session.assign("l2", "2*l + 500mm").unwrap();
+        let l2 = session.lookup("l2").unwrap();
+        assert!(l2.ends_with('m'));
+        assert!((l2.trim_end_matches('m').parse::<f64>().unwrap() - 4.5f64).abs() < 1e-12);
+    }
+
+    #[test]
+    fn arguments_in_quotes() {
+        assert_eq!(split_arguments("wire  \"l = 1m\" a=1mm2"), ["wire", "l = 1m", "a=1mm2"]);
+    }
+}
@@ -0,0 +1,166 @@
+// the forms of the specific resistance. Elect calculates with ρ in Ω·mm²/m, but the data sheets
+// also give ρ in Ω·m, µΩ·cm or Ω·cmil/ft, the conductivity σ in S/m, MS/m, m/(Ω·mm²) or %IACS and
+// for thin films and pcb copper the sheet resistance in Ω/sq. every form is turned into ρ in
+// Ω·mm²/m here and back
+use super::{format_value, parse_value};
+use convert::significant;
+use expression;
+use quantity::{Dimension, Quantity};
+
+const RESISTIVITY: Dimension = Dimension([3, 1, -3, -2, 0]);
+const CONDUCTIVITY: Dimension = Dimension([-3, -1, 3, 2, 0]);
+
+// the thickness of 1 oz copper on a pcb (1 oz per square foot) in mm
+pub const OUNCE: f64 = 0.0347903f64;
+
+// the name of the form, the unit as expression and if it is a conductivity
+static FORMS: [(&str, &str, bool); 8] = [
+    ("Ω·mm²/m", "ohmxmm2/m", false),
+    ("Ω·m", "ohm*m", false),
+    ("µΩ·cm", "uohm*cm", false),
+    ("Ω·cmil/ft", "ohm*cmil/ft", false),
+    ("S/m", "S/m", true),
+    ("MS/m", "MS/m", true),
+    ("m/(Ω·mm²)", "m/(ohm*mm2)", true),
+    ("%IACS", "%IACS", true),
+];
+
+// the form of a searched value like sigma, MS/m or µΩ·cm. the greek names and a few ascii
+// spellings are taken too
+pub fn form(name: &str) -> Option<&'static str> {
+    let name = match name {
+        "sigma" | "σ" | "kappa" | "κ" | "conductivity" => "MS/m",
+        "ohm*m" | "ohmm" | "Ωm" => "Ω·m",
+        "uohm*cm" | "uohmcm" | "µΩcm" => "µΩ·cm",
+        "ohm*cmil/ft" | "ohmcmil/ft" => "Ω·cmil/ft",
+        "iacs" => "%IACS",
+        _ => name,
+    };
+    FORMS.iter().find(|form| form.0 == name || form.1 == name).map(|form| form.0)
+}
+
+pub fn is_conductivity(form: &str) -> bool {
+    FORMS.iter().any(|known| known.0 == form && known.2)
+}
+
+// ρ in Ω·mm²/m in the unit of the form or in any other unit of a resistivity or conductivity
+pub fn rho_in(rho: f64, unit: &str) -> Result<f64, &'static str> {
+    let unit = match FORMS.iter().find(|form| form.0 == unit) {
+        Some(form) => form.1,
+        None => unit,
+    };
+    let unit = match expression::evaluate(unit, &|_| None) {
+        Ok(unit) => unit,
+        Err(_) => return Err("unit of the specific resistance not known"),
+    };
+    let rho = Quantity::new(rho * 1e-6f64, RESISTIVITY);
+    if unit.dimension == RESISTIVITY {
+        rho.in_unit(&unit)
+    } else if unit.dimension == CONDUCTIVITY {
+        Quantity::number(1f64).divided(&rho)?.in_unit(&unit)
+    } else {
+        Err("this unit is no specific resistance or conductivity")
+    }
+}
+
+// ρ in Ω·mm²/m if the input is a resistivity or a conductivity like 58MS/m, None for everything
+// else so Elect can look at it
+pub fn parse_rho(input: &str) -> Option<Result<f64, &'static str>> {
+    let value = expression::evaluate(input, &|_| None).ok()?;
+    if value.dimension == RESISTIVITY {
+        Some(Ok(value.value / 1e-6f64))
+    } else if value.dimension == CONDUCTIVITY {
+        if value.value > 0f64 {
+            Some(Ok(1f64 / value.value / 1e-6f64))
+        } else {
+            Some(Err("the conductivity has to be positive"))
+        }
+    } else {
+        None
+    }
+}
+
+// the sheet resistance in Ω of an input like 0.5mΩ/sq, 0.5 mohm / □ or 5μΩ/sq with the greek mu
+pub fn parse_sheet(input: &str) -> Option<Result<f64, &'static str>> {
+    let input = input.trim().replace('μ', "µ");
+    ["sq", "□"]
+        .iter()
+        .find_map(|square| input.strip_suffix(square))
+        .and_then(|resistance| resistance.trim_end().strip_suffix('/'))
+        .map(|resistance| parse_value(resistance, &["ohm", "Ω"]))
+}
+
+// the thickness of a film or the copper in mm, like 35µm or 1oz
+pub fn parse_thickness(input: &str) -> Result<f64, &'static str> {
+    let thickness = match input.trim().strip_suffix("oz") {
+        Some(ounces) => parse_value(ounces, &[])? * OUNCE,
+        None => parse_value(input, &["m"])? * 1000f64,
+    };
+    if thickness > 0f64 {
+        Ok(thickness)
+    } else {
+        Err("the thickness has to be positive")
+    }
+}
+
+// all forms of ρ in Ω·mm²/m, with the sheet resistance if the thickness in mm is known
+pub fn print_forms(rho: f64, thickness: Option<f64>) {
+    let mut resistivities: Vec<String> = Vec::new();
+    let mut conductivities: Vec<String> = Vec::new();
+    for form in FORMS.iter() {
+        if let Ok(value) = rho_in(rho, form.0) {
+            let value = significant(value);
+            // %IACS is written without space
+            let text = if form.0.starts_with('%') {
+                format!("{}{}", value, form.0)
+            } else {
+                format!("{} {}", value, form.0)
+            };
+            if form.2 {
+                conductivities.push(text);
+            } else {
+                resistivities.push(text);
+            }
+        }
+    }
+    println!("ρ = {}", resistivities.join(" = "));
+    println!("σ = {}", conductivities.join(" = "));
+    if let Some(thickness) = thickness {
+        println!(
+            "Rs = {}/sq at the thickness of {}",
+            format_value(rho / (thickness * 1000f64), "Ω"),
+            format_value(thickness / 1000f64, "m")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sheet_with_pre_units() {
+        for input in ["0.5mΩ/sq", "0.5mohm/□", "0.5 mΩ / sq", "500µΩ/sq", "500μΩ/sq"] {
+            assert!((parse_sheet(input).unwrap().unwrap() - 0.5e-3f64).abs() < 1e-15, "{}", input);
+        }
+        assert!((parse_sheet("2kΩ/sq").unwrap().unwrap() - 2000f64).abs() < 1e-9);
+        assert!(parse_sheet("0.5mΩ").is_none());
+        assert!(parse_sheet("0.5mV/sq").unwrap().is_err());
+    }
+
+    #[test]
+    fn forms_of_copper() {
+        // 58 MS/m and 1.72 µΩ·cm are both about 0.0172 Ω·mm²/m
+        assert!((parse_rho("58MS/m").unwrap().unwrap() - 1f64 / 58f64).abs() < 1e-12);
+        assert!((parse_rho("1.72uohm*cm").unwrap().unwrap() - 0.0172f64).abs() < 1e-12);
+        assert!((rho_in(0.0172f64, "µΩ·cm").unwrap() - 1.72f64).abs() < 1e-9);
+        assert!(parse_rho("5m").is_none());
+    }
+
+    #[test]
+    fn thickness_in_ounces() {
+        assert!((parse_thickness("1oz").unwrap() - OUNCE).abs() < 1e-15);
+        assert!((parse_thickness("35um").unwrap() - 0.035f64).abs() < 1e-15);
+        assert!(parse_thickness("0m").is_err());
+    }
+}
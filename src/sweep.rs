@@ -0,0 +1,430 @@
+// parameter sweeps over Elect and URI. one or two of the given values can be a sweep instead of a
+// single value, the calculation is done for every point and printed as table, csv or json:
+//   1m..100m:5m       linear from 1 m to 100 m in steps of 5 m
+//   1m..100m lin 20   20 points linear
+//   10ohm..1Mohm log 5   5 points per decade
+//   {copper, silver, gold}   a list of values or materials
+use std::f64;
+
+use super::split_given;
+use tolerance::{Calculation, Given};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Table,
+    Csv,
+    Json,
+}
+
+// one swept variable with all its points
+#[derive(Clone, Debug)]
+pub struct Variable {
+    pub name: String,
+    pub unit: String,
+    position: usize, // in the given values
+    pub points: Vec<Given>,
+}
+
+// one row of the sweep, the points of the swept variables and the result
+#[derive(Clone, Debug)]
+pub struct Row {
+    pub values: Vec<Given>,
+    pub result: Option<f64>,
+}
+
+// the most points one sweep can have, so a typo doesn't calculate forever
+const MAX_POINTS: usize = 100000;
+
+// a point of a sweep as given value, so it can be used like a typed one
+fn point(value: f64, unit: &str) -> Given {
+    Given {
+        value: Some(value),
+        unit: unit.to_string(),
+        raw: format!("{}{}", value, unit),
+        tolerance: None,
+    }
+}
+
+// the number of a point, a material is shown with its name and not as specific resistance
+pub fn numeric(given: &Given) -> Option<f64> {
+    if given.raw.starts_with(|c: char| c.is_ascii_digit() || c == '.' || c == '-') {
+        given.value
+    } else {
+        None
+    }
+}
+
+fn is_sweep(value: &str) -> bool {
+    value.contains("..") || value.starts_with('{') || value.starts_with('[')
+}
+
+// all points of the sweep given as string
+fn parse_points(value: &str, calculation: Calculation) -> Result<Vec<Given>, &'static str> {
+    let value = value.trim();
+    if value.starts_with('{') || value.starts_with('[') {
+        let inner = value.trim_matches(|c: char| c == '{' || c == '}' || c == '[' || c == ']');
+        let mut points: Vec<Given> = Vec::new();
+        for item in inner.split(',') {
+            if item.trim() != "" {
+                points.push(Given::new(item.trim(), calculation)?);
+            }
+        }
+        return Ok(points);
+    }
+    let split_point = if let Some(split_point) = value.find("..") {
+        split_point
+    } else {
+        return Err("sweep not parseable");
+    };
+    let start = Given::new(&value[..split_point], calculation)?;
+    let rest = &value[split_point + 2..];
+    // the end is followed by :step, lin n or log n
+    let (stop_str, kind, amount) = if let Some(position) = rest.find(':') {
+        (&rest[..position], "step", &rest[position + 1..])
+    } else if let Some(position) = rest.find(" lin ") {
+        (&rest[..position], "lin", &rest[position + 5..])
+    } else if let Some(position) = rest.find(" log ") {
+        (&rest[..position], "log", &rest[position + 5..])
+    } else {
+        (rest, "lin", "11")
+    };
+    let stop = Given::new(stop_str, calculation)?;
+    let (start_value, stop_value) = match (start.value, stop.value) {
+        (Some(start_value), Some(stop_value)) if start.unit == stop.unit => {
+            (start_value, stop_value)
+        }
+        _ => return Err("start and end of the sweep need the same unit"),
+    };
+    let unit = start.unit.clone();
+    let mut points: Vec<Given> = Vec::new();
+    match kind {
+        "step" => {
+            let step = Given::new(amount, calculation)?;
+            let step = match step.value {
+                Some(step) if step > 0f64 => step,
+                _ => return Err("the step has to be a positive number"),
+            };
+            // the steps are counted, so the rounding errors don't add up
+            let count = ((stop_value - start_value) / step + 1e-9).floor();
+            if count < 0f64 || count as usize >= MAX_POINTS {
+                return Err("the sweep has too many or no points");
+            }
+            for k in 0..(count as usize + 1) {
+                points.push(point(start_value + k as f64 * step, &unit));
+            }
+        }
+        "lin" => {
+            let n = match amount.trim().parse::<usize>() {
+                Ok(n) if (2..MAX_POINTS).contains(&n) => n,
+                _ => return Err("number of points not parseable"),
+            };
+            for k in 0..n {
+                let value = start_value + (stop_value - start_value) * k as f64 / (n - 1) as f64;
+                points.push(point(value, &unit));
+            }
+        }
+        _ => {
+            let per_decade = match amount.trim().parse::<usize>() {
+                Ok(n) if n >= 1 => n as f64,
+                _ => return Err("number of points per decade not parseable"),
+            };
+            if !(start_value > 0f64 && stop_value > start_value) {
+                return Err("a logarithmic sweep needs 0 < start < end");
+            }
+            let count = ((stop_value / start_value).log10() * per_decade + 1e-9).floor();
+            if count as usize >= MAX_POINTS {
+                return Err("the sweep has too many points");
+            }
+            for k in 0..(count as usize + 1) {
+                points.push(point(start_value * 10f64.powf(k as f64 / per_decade), &unit));
+            }
+        }
+    }
+    Ok(points)
+}
+
+#[derive(Clone, Debug)]
+pub struct Sweep {
+    pub calculation: Calculation,
+    given: Vec<Given>,
+    pub variables: Vec<Variable>,
+    pub format: Format,
+    pub rows: Vec<Row>,
+    pub result_index: Option<usize>,
+}
+impl Sweep {
+    pub fn new(s: Vec<String>, calculation: Calculation) -> Result<Sweep, &'static str> {
+        let mut sweep = Sweep {
+            calculation,
+            given: Vec::new(),
+            variables: Vec::new(),
+            format: Format::Table,
+            rows: Vec::new(),
+            result_index: None,
+        };
+        for i in s.iter() {
+            // a name comes before the range, a ':' after the range is the step like in 0V..24V:6V
+            let (name, value) = match (i.find(['=', ':']), i.find("..")) {
+                (Some(split_point), Some(range)) if split_point > range => {
+                    ("".to_string(), i.trim().to_string())
+                }
+                _ => split_given(i),
+            };
+            if name == "format" {
+                sweep.format = match value.to_lowercase().as_str() {
+                    "table" => Format::Table,
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    _ => return Err("format not known"),
+                };
+            } else if is_sweep(&value) {
+                let points = parse_points(&value, calculation)?;
+                if points.is_empty() {
+                    return Err("the sweep has no points");
+                }
+                // Elect and URI can't take a negative value, only the temperature can be one
+                let negative = |point: &Given| point.value.is_some_and(|value| value < 0f64);
+                if points.iter().any(|point| point.unit != "°C" && negative(point)) {
+                    return Err("the sweep has negative values, only a temperature can be one");
+                }
+                let unit = points[0].unit.clone();
+                sweep.variables.push(Variable {
+                    name: if !name.is_empty() { name } else { unit.clone() },
+                    unit,
+                    position: sweep.given.len(),
+                    points: points.clone(),
+                });
+                // the place in the given values is filled for every point
+                sweep.given.push(points[0].clone());
+            } else {
+                sweep.given.push(Given::new(i, calculation)?);
+            }
+        }
+        match sweep.variables.len() {
+            1 | 2 => {}
+            0 => return Err("give me at least one sweep"),
+            _ => return Err("only two variables can be swept together"),
+        }
+        Ok(sweep)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        self.rows.clear();
+        let inner_points = if self.variables.len() == 2 {
+            self.variables[1].points.clone()
+        } else {
+            vec![point(f64::NAN, "")]
+        };
+        for outer in self.variables[0].points.iter() {
+            for inner in inner_points.iter() {
+                let mut given = self.given.clone();
+                let mut values = vec![outer.clone()];
+                given[self.variables[0].position] = outer.clone();
+                if self.variables.len() == 2 {
+                    given[self.variables[1].position] = inner.clone();
+                    values.push(inner.clone());
+                }
+                let strings: Vec<String> = given.iter().map(|given| given.scaled(1f64)).collect();
+                let result = match self.calculation.evaluate(strings) {
+                    Some((index, value)) => {
+                        self.result_index = Some(index);
+                        Some(value)
+                    }
+                    None => None,
+                };
+                self.rows.push(Row {
+                    values,
+                    result,
+                });
+            }
+        }
+        if self.result_index.is_none() {
+            return Err("the calculation has no result for any point");
+        }
+        if should_print {
+            self.print_res()
+        }
+        Ok(())
+    }
+    // the headers of the columns with the units
+    pub fn headers(&self) -> Vec<String> {
+        let mut headers: Vec<String> = self
+            .variables
+            .iter()
+            .map(|variable| {
+                if !variable.unit.is_empty() && variable.unit != variable.name {
+                    format!("{} in {}", variable.name, variable.unit)
+                } else {
+                    variable.name.clone()
+                }
+            })
+            .collect();
+        let (name, unit) = self
+            .calculation
+            .result_name(self.result_index.unwrap_or_default());
+        headers.push(format!("{} in {}", name, unit));
+        headers
+    }
+    fn print_res(&self) {
+        for line in self.lines() {
+            println!("{}", line);
+        }
+    }
+    // the rows of the sweep in the format, one string for every line
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+        let headers = self.headers();
+        let cell = |given: &Given| {
+            if let Some(value) = numeric(given) {
+                format!("{}", value)
+            } else {
+                given.raw.clone()
+            }
+        };
+        // a point without a result is marked in the table and left empty in the csv
+        let result = |row: &Row| {
+            if let Some(result) = row.result {
+                format!("{}", result)
+            } else if self.format == Format::Table {
+                "-".to_string()
+            } else {
+                "".to_string()
+            }
+        };
+        match self.format {
+            Format::Table => {
+                let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+                for row in self.rows.iter() {
+                    for (k, given) in row.values.iter().enumerate() {
+                        widths[k] = widths[k].max(cell(given).chars().count());
+                    }
+                    let last = widths.len() - 1;
+                    widths[last] = widths[last].max(result(row).chars().count());
+                }
+                let line: Vec<String> = headers
+                    .iter()
+                    .zip(widths.iter())
+                    .map(|(h, w)| format!("{:>1$}", h, w))
+                    .collect();
+                lines.push(line.join(" | "));
+                let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+                lines.push(rule.join("-+-"));
+                for row in self.rows.iter() {
+                    let mut cells: Vec<String> = row.values.iter().map(&cell).collect();
+                    cells.push(result(row));
+                    let line: Vec<String> = cells
+                        .iter()
+                        .zip(widths.iter())
+                        .map(|(c, w)| format!("{:>1$}", c, w))
+                        .collect();
+                    lines.push(line.join(" | "));
+                }
+            }
+            Format::Csv => {
+                lines.push(headers.join(","));
+                for row in self.rows.iter() {
+                    let mut cells: Vec<String> = row.values.iter().map(&cell).collect();
+                    cells.push(result(row));
+                    lines.push(cells.join(","));
+                }
+            }
+            Format::Json => {
+                let quoted = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+                lines.push("[".to_string());
+                for (n, row) in self.rows.iter().enumerate() {
+                    let mut fields: Vec<String> = Vec::new();
+                    for (k, given) in row.values.iter().enumerate() {
+                        let value = if let Some(value) = numeric(given) {
+                            format!("{}", value)
+                        } else {
+                            quoted(&given.raw)
+                        };
+                        fields.push(format!("{}: {}", quoted(&headers[k]), value));
+                    }
+                    let value = if let Some(value) = row.result {
+                        format!("{}", value)
+                    } else {
+                        "null".to_string()
+                    };
+                    fields.push(format!("{}: {}", quoted(&headers[headers.len() - 1]), value));
+                    let comma = if n + 1 < self.rows.len() { "," } else { "" };
+                    lines.push(format!("  {{{}}}{}", fields.join(", "), comma));
+                }
+                lines.push("]".to_string());
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::strings;
+
+    fn sweep(given: &[&str]) -> Result<Sweep, &'static str> {
+        Sweep::new(strings(given), Calculation::Uri)
+    }
+
+    #[test]
+    fn step_lin_and_log_points() {
+        let values = |value: &str| -> Vec<f64> {
+            let points = parse_points(value, Calculation::Uri).unwrap();
+            points.iter().map(|point| point.value.unwrap()).collect()
+        };
+        assert_eq!(values("0V..24V:6V"), vec![0f64, 6f64, 12f64, 18f64, 24f64]);
+        assert_eq!(values("0V..1V lin 3"), vec![0f64, 0.5f64, 1f64]);
+        assert_eq!(values("1V..100V log 1").len(), 3);
+        assert_eq!(values("{1V, 5V}"), vec![1f64, 5f64]);
+    }
+
+    #[test]
+    fn unnamed_sweep_with_step() {
+        let unnamed = sweep(&["0V..24V:6V", "1kohm"]).unwrap();
+        assert_eq!(unnamed.variables[0].name, "V");
+        assert_eq!(unnamed.variables[0].points.len(), 5);
+        let named = sweep(&["u:0V..24V:6V", "1kohm"]).unwrap();
+        assert_eq!(named.variables[0].name, "u");
+        assert_eq!(named.variables[0].points.len(), 5);
+    }
+
+    #[test]
+    fn csv_and_json() {
+        let mut csv = sweep(&["u=12V", "r=10ohm..1kohm log 1", "format=csv"]).unwrap();
+        csv.calc(false).unwrap();
+        assert_eq!(csv.lines(), vec!["r in ohm,I in A", "10,1.2", "100,0.12", "1000,0.012"]);
+        let mut json = sweep(&["u=12V", "r={10ohm, 100ohm}", "format=json"]).unwrap();
+        json.calc(false).unwrap();
+        assert_eq!(
+            json.lines(),
+            vec![
+                "[",
+                "  {\"r in ohm\": 10, \"I in A\": 1.2},",
+                "  {\"r in ohm\": 100, \"I in A\": 0.12}",
+                "]",
+            ]
+        );
+    }
+
+    #[test]
+    fn two_variables() {
+        // the first variable is the outer loop, the second one the inner
+        let mut both = sweep(&["u={6V, 12V}", "r={10ohm, 100ohm}", "format=csv"]).unwrap();
+        both.calc(false).unwrap();
+        assert_eq!(
+            both.lines(),
+            vec!["u in V,r in ohm,I in A", "6,10,0.6", "6,100,0.06", "12,10,1.2", "12,100,0.12"]
+        );
+        assert!(sweep(&["u={6V, 12V}", "r={10ohm, 100ohm}", "i={1A, 2A}"]).is_err());
+        assert!(sweep(&["u=12V", "r=10ohm"]).is_err());
+    }
+
+    #[test]
+    fn zero_and_negative_points() {
+        // no current without a voltage, but still a result
+        let mut zero = sweep(&["u=0V..12V:6V", "r=6ohm"]).unwrap();
+        zero.calc(false).unwrap();
+        let results: Vec<Option<f64>> = zero.rows.iter().map(|row| row.result).collect();
+        assert_eq!(results, vec![Some(0f64), Some(1f64), Some(2f64)]);
+        assert!(sweep(&["u=-12V..12V:12V", "r=6ohm"]).is_err());
+    }
+}
@@ -0,0 +1,541 @@
+// calculations for the three phase (polyphase) systems we use for the supply lines of machines.
+// like in Elect and URI every struct takes the given values as strings, the missing values are
+// calculated and printed afterwards
+use std::f64;
+
+use super::{parse_value, split_given, Elect};
+
+// over-determined values have to agree this well, 400 V and 230 V are taken as one star system
+const TOLERANCE: f64 = 0.01f64;
+
+// true if both values are there and differ more than the tolerance
+fn differ(a: Option<f64>, b: Option<f64>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => (a - b).abs() > TOLERANCE * a.abs().max(b.abs()),
+        _ => false,
+    }
+}
+
+// the two ways the three windings or loads can be connected
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Connection {
+    Star,  // Stern, Y
+    Delta, // Dreieck, Δ
+}
+
+// the three phase system with the line and phase values and the power
+#[derive(Clone, Debug)]
+pub struct ThreePhase {
+    connection: Connection,
+    u_line: Option<f64>,  //V
+    u_phase: Option<f64>, //V
+    i_line: Option<f64>,  //A
+    i_phase: Option<f64>, //A
+    cos_phi: Option<f64>,
+    p: Option<f64>, //W
+    q: Option<f64>, //var
+    s: Option<f64>, //VA
+}
+impl ThreePhase {
+    pub fn new(s: Vec<String>) -> Result<ThreePhase, &'static str> {
+        let mut three_phase = ThreePhase {
+            connection: Connection::Star,
+            u_line: None,
+            u_phase: None,
+            i_line: None,
+            i_phase: None,
+            cos_phi: None,
+            p: None,
+            q: None,
+            s: None,
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "ul" | "u" => three_phase.u_line = Some(parse_value(&value, &["V"])?),
+                "uph" | "ustr" => three_phase.u_phase = Some(parse_value(&value, &["V"])?),
+                "il" | "i" => three_phase.i_line = Some(parse_value(&value, &["A"])?),
+                "iph" | "istr" => three_phase.i_phase = Some(parse_value(&value, &["A"])?),
+                "cos" | "cosphi" | "pf" => three_phase.cos_phi = Some(parse_value(&value, &[])?),
+                "p" => three_phase.p = Some(parse_value(&value, &["W"])?),
+                "q" => three_phase.q = Some(parse_value(&value, &["var"])?),
+                "s" => three_phase.s = Some(parse_value(&value, &["VA"])?),
+                "" => {
+                    three_phase.connection = match value.to_lowercase().as_str() {
+                        "star" | "stern" | "y" => Connection::Star,
+                        "delta" | "dreieck" | "d" => Connection::Delta,
+                        _ => return Err("connection not known"),
+                    }
+                }
+                _ => return Err("input is strange"),
+            }
+        }
+        if let Some(cos_phi) = three_phase.cos_phi {
+            if !(0f64..=1f64).contains(&cos_phi) {
+                return Err("cos phi has to be between 0 and 1");
+            }
+        }
+        Ok(three_phase)
+    }
+    // unlike in URI there are a lot of possible combinations of given values. so we use every
+    // rule we know as long as one of them finds a new value
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        let sqrt3 = 3f64.sqrt();
+        let mut calculated = false;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            // line and phase values depend on the connection
+            let (u_factor, i_factor) = match self.connection {
+                Connection::Star => (sqrt3, 1f64),
+                Connection::Delta => (1f64, sqrt3),
+            };
+            if self.u_line.is_none() {
+                if let Some(u_phase) = self.u_phase {
+                    self.u_line = Some(u_phase * u_factor);
+                    changed = true;
+                }
+            }
+            if self.u_phase.is_none() {
+                if let Some(u_line) = self.u_line {
+                    self.u_phase = Some(u_line / u_factor);
+                    changed = true;
+                }
+            }
+            if self.i_line.is_none() {
+                if let Some(i_phase) = self.i_phase {
+                    self.i_line = Some(i_phase * i_factor);
+                    changed = true;
+                }
+            }
+            if self.i_phase.is_none() {
+                if let Some(i_line) = self.i_line {
+                    self.i_phase = Some(i_line / i_factor);
+                    changed = true;
+                }
+            }
+            // S = √3·UL·IL
+            if self.s.is_none() {
+                if let (Some(u), Some(i)) = (self.u_line, self.i_line) {
+                    self.s = Some(sqrt3 * u * i);
+                    changed = true;
+                } else if let (Some(p), Some(q)) = (self.p, self.q) {
+                    self.s = Some((p * p + q * q).sqrt());
+                    changed = true;
+                } else if let (Some(p), Some(cos_phi)) = (self.p, self.cos_phi) {
+                    if cos_phi > 0f64 {
+                        self.s = Some(p / cos_phi);
+                        changed = true;
+                    }
+                }
+            }
+            if self.i_line.is_none() {
+                if let (Some(s), Some(u)) = (self.s, self.u_line) {
+                    if u > 0f64 {
+                        self.i_line = Some(s / (sqrt3 * u));
+                        changed = true;
+                    }
+                }
+            }
+            if self.u_line.is_none() {
+                if let (Some(s), Some(i)) = (self.s, self.i_line) {
+                    if i > 0f64 {
+                        self.u_line = Some(s / (sqrt3 * i));
+                        changed = true;
+                    }
+                }
+            }
+            if self.cos_phi.is_none() {
+                if let (Some(p), Some(s)) = (self.p, self.s) {
+                    if s > 0f64 {
+                        self.cos_phi = Some(p / s);
+                        changed = true;
+                    }
+                }
+            }
+            if self.p.is_none() {
+                if let (Some(s), Some(cos_phi)) = (self.s, self.cos_phi) {
+                    self.p = Some(s * cos_phi);
+                    changed = true;
+                }
+            }
+            if self.q.is_none() {
+                if let (Some(s), Some(cos_phi)) = (self.s, self.cos_phi) {
+                    self.q = Some(s * (1f64 - cos_phi * cos_phi).sqrt());
+                    changed = true;
+                }
+            }
+            calculated |= changed;
+        }
+        if !calculated {
+            return Err("give me more values, like ul and il or p, ul and cos phi");
+        }
+        self.check()?;
+        if should_print {
+            self.print_res()
+        }
+        Ok(())
+    }
+    // the rules only fill the missing values, so given values which don't fit together show up
+    // here when every rule is checked again
+    fn check(&self) -> Result<(), &'static str> {
+        let sqrt3 = 3f64.sqrt();
+        let (u_factor, i_factor) = match self.connection {
+            Connection::Star => (sqrt3, 1f64),
+            Connection::Delta => (1f64, sqrt3),
+        };
+        if differ(self.u_line, self.u_phase.map(|u_phase| u_phase * u_factor)) {
+            return Err("the line and phase voltage don't fit to the connection");
+        }
+        if differ(self.i_line, self.i_phase.map(|i_phase| i_phase * i_factor)) {
+            return Err("the line and phase current don't fit to the connection");
+        }
+        if let Some(cos_phi) = self.cos_phi {
+            if !(0f64..=1f64).contains(&cos_phi) {
+                return Err("the active power can't be larger than the apparent power");
+            }
+        }
+        let s_ui = match (self.u_line, self.i_line) {
+            (Some(u), Some(i)) => Some(sqrt3 * u * i),
+            _ => None,
+        };
+        let s_pq = match (self.p, self.q) {
+            (Some(p), Some(q)) => Some((p * p + q * q).sqrt()),
+            _ => None,
+        };
+        let p_s = match (self.s, self.cos_phi) {
+            (Some(s), Some(cos_phi)) => Some(s * cos_phi),
+            _ => None,
+        };
+        if differ(self.s, s_ui) || differ(self.s, s_pq) || differ(self.p, p_s) {
+            return Err("the given voltage, current and power don't fit together");
+        }
+        Ok(())
+    }
+    fn print_res(&self) {
+        let connection = match self.connection {
+            Connection::Star => "star",
+            Connection::Delta => "delta",
+        };
+        println!("The values of the three phase system in {} connection are:", connection);
+        if let Some(u) = self.u_line {
+            println!("line voltage UL: {} V", u)
+        }
+        if let Some(u) = self.u_phase {
+            println!("phase voltage Uph: {} V", u)
+        }
+        if let Some(i) = self.i_line {
+            println!("line current IL: {} A", i)
+        }
+        if let Some(i) = self.i_phase {
+            println!("phase current Iph: {} A", i)
+        }
+        if let Some(cos_phi) = self.cos_phi {
+            println!("power factor cos φ: {}", cos_phi)
+        }
+        if let Some(p) = self.p {
+            println!("active power P: {} W", p)
+        }
+        if let Some(q) = self.q {
+            println!("reactive power Q: {} var", q)
+        }
+        if let Some(s) = self.s {
+            println!("apparent power S: {} VA", s)
+        }
+    }
+}
+
+// conversion of three resistors (or impedances with the same angle) between star and delta
+#[derive(Clone, Debug, Default)]
+pub struct StarDelta {
+    star: Option<[f64; 3]>,  //R1, R2, R3 ohm
+    delta: Option<[f64; 3]>, //R12, R23, R31 ohm
+}
+impl StarDelta {
+    pub fn new(s: Vec<String>) -> Result<StarDelta, &'static str> {
+        let mut star: [Option<f64>; 3] = [None; 3];
+        let mut delta: [Option<f64>; 3] = [None; 3];
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            let r = parse_value(&value, &["ohm", "Ω"])?;
+            match name.as_str() {
+                "r1" => star[0] = Some(r),
+                "r2" => star[1] = Some(r),
+                "r3" => star[2] = Some(r),
+                "r12" => delta[0] = Some(r),
+                "r23" => delta[1] = Some(r),
+                "r31" => delta[2] = Some(r),
+                _ => return Err("input is strange"),
+            }
+        }
+        // the other side is calculated, so giving values of both would be over-determined
+        if star.iter().any(|r| r.is_some()) && delta.iter().any(|r| r.is_some()) {
+            return Err("give me either r1, r2 and r3 or r12, r23 and r31");
+        }
+        let mut star_delta: StarDelta = Default::default();
+        if let [Some(r1), Some(r2), Some(r3)] = star {
+            star_delta.star = Some([r1, r2, r3]);
+        }
+        if let [Some(r12), Some(r23), Some(r31)] = delta {
+            star_delta.delta = Some([r12, r23, r31]);
+        }
+        if star_delta.star.is_some() == star_delta.delta.is_some() {
+            return Err("give me either r1, r2 and r3 or r12, r23 and r31");
+        }
+        Ok(star_delta)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        if let Some([r1, r2, r3]) = self.star {
+            // star to delta: R12 = R1 + R2 + R1·R2/R3
+            if r1 > 0f64 && r2 > 0f64 && r3 > 0f64 {
+                self.delta = Some([
+                    r1 + r2 + r1 * r2 / r3,
+                    r2 + r3 + r2 * r3 / r1,
+                    r3 + r1 + r3 * r1 / r2,
+                ]);
+                if should_print {
+                    println!(
+                        "The delta equivalent of the star R1 = {} Ω, R2 = {} Ω, R3 = {} Ω is:",
+                        r1, r2, r3
+                    );
+                    self.print_res(false);
+                }
+            }
+        } else if let Some([r12, r23, r31]) = self.delta {
+            // delta to star: R1 = R12·R31 / (R12 + R23 + R31)
+            let sum = r12 + r23 + r31;
+            if sum > 0f64 {
+                self.star = Some([r12 * r31 / sum, r23 * r12 / sum, r31 * r23 / sum]);
+                if should_print {
+                    println!(
+                        "The star equivalent of the delta R12 = {} Ω, R23 = {} Ω, R31 = {} Ω is:",
+                        r12, r23, r31
+                    );
+                    self.print_res(true);
+                }
+            }
+        }
+        if self.star.is_none() || self.delta.is_none() {
+            return Err("the resistances have to be positive");
+        }
+        Ok(())
+    }
+    fn print_res(&self, star: bool) {
+        if star {
+            if let Some([r1, r2, r3]) = self.star {
+                println!("R1 = {} Ω, R2 = {} Ω, R3 = {} Ω", r1, r2, r3)
+            }
+        } else if let Some([r12, r23, r31]) = self.delta {
+            println!("R12 = {} Ω, R23 = {} Ω, R31 = {} Ω", r12, r23, r31)
+        }
+    }
+}
+
+// the current in the neutral conductor of an unbalanced star load. the phase currents are added
+// as vectors, the voltage of L1 is at 0°, L2 at -120° and L3 at 120°. the loads are taken as
+// inductive, so the current lags the voltage by φ
+#[derive(Clone, Debug)]
+pub struct NeutralCurrent {
+    currents: [f64; 3], //A
+    cos_phi: [f64; 3],
+    neutral: Option<f64>, //A
+}
+impl NeutralCurrent {
+    pub fn new(s: Vec<String>) -> Result<NeutralCurrent, &'static str> {
+        let mut neutral_current = NeutralCurrent {
+            currents: [0f64; 3],
+            cos_phi: [1f64; 3],
+            neutral: None,
+        };
+        if !s.iter().any(|i| ["i1", "i2", "i3"].contains(&split_given(i).0.as_str())) {
+            return Err("give me at least one of the phase currents i1, i2 and i3");
+        }
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "i1" => neutral_current.currents[0] = parse_value(&value, &["A"])?,
+                "i2" => neutral_current.currents[1] = parse_value(&value, &["A"])?,
+                "i3" => neutral_current.currents[2] = parse_value(&value, &["A"])?,
+                "cos1" => neutral_current.cos_phi[0] = parse_value(&value, &[])?,
+                "cos2" => neutral_current.cos_phi[1] = parse_value(&value, &[])?,
+                "cos3" => neutral_current.cos_phi[2] = parse_value(&value, &[])?,
+                _ => return Err("input is strange"),
+            }
+        }
+        for cos_phi in neutral_current.cos_phi.iter() {
+            if *cos_phi < 0f64 || *cos_phi > 1f64 {
+                return Err("cos phi has to be between 0 and 1");
+            }
+        }
+        Ok(neutral_current)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        let voltage_angles: [f64; 3] = [0f64, -120f64, 120f64];
+        let mut re = 0f64;
+        let mut im = 0f64;
+        for (k, voltage_angle) in voltage_angles.iter().enumerate() {
+            let angle = voltage_angle.to_radians() - self.cos_phi[k].acos();
+            re += self.currents[k] * angle.cos();
+            im += self.currents[k] * angle.sin();
+        }
+        self.neutral = Some((re * re + im * im).sqrt());
+        if should_print {
+            self.print_res()
+        }
+        Ok(())
+    }
+    fn print_res(&self) {
+        if let Some(neutral) = self.neutral {
+            println!(
+                "The neutral current for I1 = {} A, I2 = {} A and I3 = {} A is: {} A",
+                self.currents[0], self.currents[1], self.currents[2], neutral
+            )
+        }
+    }
+}
+
+// the voltage drop of a three phase cable. the resistance of one conductor is calculated with
+// Elect, so the length, the area and the material are given the same way as there
+#[derive(Clone, Debug)]
+pub struct CableDrop {
+    conductor: Elect,
+    i_line: Option<f64>, //A
+    u_line: Option<f64>, //V
+    cos_phi: f64,
+    x: f64,                  //ohm/km reactance per conductor
+    u_drop: Option<f64>,     //V
+    power_loss: Option<f64>, //W
+}
+impl CableDrop {
+    pub fn new(s: Vec<String>) -> Result<CableDrop, &'static str> {
+        let mut elect_strings: Vec<String> = Vec::new();
+        let mut i_line = None;
+        let mut u_line = None;
+        let mut cos_phi = 1f64;
+        let mut x = 0f64;
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "i" | "il" => i_line = Some(parse_value(&value, &["A"])?),
+                "u" | "ul" => u_line = Some(parse_value(&value, &["V"])?),
+                "cos" | "cosphi" | "pf" => cos_phi = parse_value(&value, &[])?,
+                "x" => x = parse_value(&value, &["ohm/km", "Ω/km"])?,
+                // everything else belongs to the conductor
+                _ => elect_strings.push(value),
+            }
+        }
+        if !(0f64..=1f64).contains(&cos_phi) {
+            return Err("cos phi has to be between 0 and 1");
+        }
+        let mut conductor = Elect::new(elect_strings)?;
+        conductor.resistor = None;
+        Ok(CableDrop {
+            conductor,
+            i_line,
+            u_line,
+            cos_phi,
+            x,
+            u_drop: None,
+            power_loss: None,
+        })
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        self.conductor.calc_resistor();
+        let r = self.conductor.resistor.unwrap_or(f64::NAN);
+        let length = self.conductor.length.unwrap_or(f64::NAN);
+        let i = match self.i_line {
+            Some(i) => i,
+            None => return Err("give me the line current i"),
+        };
+        if r.is_nan() {
+            return Err("give me the length, the area and the material of the conductor");
+        }
+        let x = self.x * length / 1000f64;
+        let sin_phi = (1f64 - self.cos_phi * self.cos_phi).sqrt();
+        // ΔU = √3·I·(R·cos φ + X·sin φ) for the line voltage
+        self.u_drop = Some(3f64.sqrt() * i * (r * self.cos_phi + x * sin_phi));
+        self.power_loss = Some(3f64 * i * i * r);
+        if should_print {
+            self.print_res()
+        }
+        Ok(())
+    }
+    fn print_res(&self) {
+        let r = self.conductor.resistor.unwrap_or(0f64);
+        if let (Some(u_drop), Some(power_loss)) = (self.u_drop, self.power_loss) {
+            println!("The resistance of one conductor is: {} Ω", r);
+            println!("The voltage drop of the three phase cable is: {} V", u_drop);
+            if let Some(u_line) = self.u_line {
+                if u_line > 0f64 {
+                    println!("That is {} % of the line voltage of {} V", u_drop / u_line * 100f64, u_line);
+                }
+            }
+            println!("The power loss in the three conductors is: {} W", power_loss);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::{calculated, strings};
+
+    fn three_phase(given: &[&str]) -> ThreePhase {
+        calculated(given, ThreePhase::new, ThreePhase::calc).unwrap()
+    }
+
+    #[test]
+    fn star_power() {
+        let three_phase = three_phase(&["ul=400V", "il=10A", "cos=0.8"]);
+        assert!((three_phase.p.unwrap() - 5542.562584220407f64).abs() < 1e-6);
+        assert!((three_phase.u_phase.unwrap() - 400f64 / 3f64.sqrt()).abs() < 1e-9);
+        assert!((three_phase.i_phase.unwrap() - 10f64).abs() < 1e-12);
+    }
+
+    #[test]
+    fn delta_phase_current() {
+        let three_phase = three_phase(&["delta", "ul=400V", "il=10A"]);
+        assert!((three_phase.u_phase.unwrap() - 400f64).abs() < 1e-12);
+        assert!((three_phase.i_phase.unwrap() - 10f64 / 3f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn nothing_or_too_much_given() {
+        assert!(ThreePhase::new(strings(&["cos=0.8"])).unwrap().calc(false).is_err());
+        let mut three_phase = ThreePhase::new(strings(&["ul=400V", "uph=100V"])).unwrap();
+        assert!(three_phase.calc(false).is_err());
+        let mut three_phase =
+            ThreePhase::new(strings(&["ul=400V", "il=10A", "s=1kVA"])).unwrap();
+        assert!(three_phase.calc(false).is_err());
+        // the rounded 230 V fits to 400 V
+        let mut three_phase = ThreePhase::new(strings(&["ul=400V", "uph=230V", "il=1A"])).unwrap();
+        assert!(three_phase.calc(false).is_ok());
+    }
+
+    #[test]
+    fn star_to_delta() {
+        let star = ["r1=10ohm", "r2=10ohm", "r3=10ohm"];
+        let mut star_delta = StarDelta::new(strings(&star)).unwrap();
+        star_delta.calc(false).unwrap();
+        assert_eq!(star_delta.delta, Some([30f64; 3]));
+        let mixed = ["r1=10ohm", "r2=10ohm", "r12=1ohm", "r23=1ohm"];
+        assert!(StarDelta::new(strings(&mixed)).is_err());
+        let mut star_delta = StarDelta::new(strings(&["r1=0ohm", "r2=10ohm", "r3=10ohm"])).unwrap();
+        assert!(star_delta.calc(false).is_err());
+    }
+
+    #[test]
+    fn balanced_neutral_current() {
+        let neutral = |given| calculated(given, NeutralCurrent::new, NeutralCurrent::calc);
+        assert!(neutral(&["i1=10A", "i2=10A", "i3=10A"]).unwrap().neutral.unwrap() < 1e-9);
+        let neutral = neutral(&["i1=10A"]).unwrap();
+        assert!((neutral.neutral.unwrap() - 10f64).abs() < 1e-9);
+        assert!(NeutralCurrent::new(strings(&["cos1=0.9"])).is_err());
+    }
+
+    #[test]
+    fn cable_drop_needs_the_current() {
+        let mut cable_drop = CableDrop::new(strings(&["copper", "100m", "2.5mm2"])).unwrap();
+        assert!(cable_drop.calc(false).is_err());
+    }
+}
@@ -0,0 +1,341 @@
+// tolerances of the given values and how they spread into the result of Elect and URI. a value
+// can be given as 4.7kohm±5% or 12V±0.5V, with a σ at the end the tolerance is a standard
+// deviation. the result is given as worst case (all corners of the tolerances) and as RSS (root
+// sum square of the single contributions)
+use std::f64;
+
+use super::{parse_value, split_given, Elect, URI};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+    pub relative: f64, // half of the band or the standard deviation, 0.05 for ±5 %
+    pub sigma: bool,
+}
+impl Tolerance {
+    // the half band for the worst case, a standard deviation counts three times
+    pub fn band(&self) -> f64 {
+        if self.sigma {
+            3f64 * self.relative
+        } else {
+            self.relative
+        }
+    }
+}
+
+// the calculations the tolerances can go through
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Calculation {
+    Elect,
+    Uri,
+}
+impl Calculation {
+    // the units of the given values, the longer ones first so mm2 isn't taken as m
+    fn units(&self) -> &'static [&'static str] {
+        match self {
+            Calculation::Elect => &["ohmxmm2/m", "mm2", "mm", "ohm", "m", "°C"],
+            Calculation::Uri => &["ohm", "V", "A"],
+        }
+    }
+    // runs the calculation and gives back the index and the value of the calculated variable
+    pub fn evaluate(&self, given: Vec<String>) -> Option<(usize, f64)> {
+        match self {
+            Calculation::Elect => {
+                let mut elect = if let Ok(elect) = Elect::new(given) {
+                    elect
+                } else {
+                    return None;
+                };
+                match elect.calc(false) {
+                    0 => elect.resistor.map(|r| (0, r)),
+                    1 => elect.rho.map(|rho| (1, rho)),
+                    2 => elect.length.map(|length| (2, length)),
+                    3 => elect.area.and_then(|area| area.a).map(|a| (3, a)),
+                    _ => None,
+                }
+            }
+            Calculation::Uri => {
+                let mut uri = if let Ok(uri) = URI::new(given) {
+                    uri
+                } else {
+                    return None;
+                };
+                let missing = [uri.u.is_none(), uri.i.is_none(), uri.r.is_none()];
+                uri.calc(false);
+                if missing[0] {
+                    uri.u.map(|u| (0, u))
+                } else if missing[1] {
+                    uri.i.map(|i| (1, i))
+                } else if missing[2] {
+                    uri.r.map(|r| (2, r))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+    // name and unit of the calculated variable
+    pub fn result_name(&self, index: usize) -> (&'static str, &'static str) {
+        match (self, index) {
+            (Calculation::Elect, 0) => ("R", "Ω"),
+            (Calculation::Elect, 1) => ("ρ", "Ω·mm²/m"),
+            (Calculation::Elect, 2) => ("l", "m"),
+            (Calculation::Elect, 3) => ("A", "mm²"),
+            (Calculation::Uri, 0) => ("U", "V"),
+            (Calculation::Uri, 1) => ("I", "A"),
+            (Calculation::Uri, 2) => ("R", "Ω"),
+            _ => ("?", ""),
+        }
+    }
+}
+
+// one given value. the number is normalised to the base unit so we can scale it, everything
+// else (like a material) stays as it is
+#[derive(Clone, Debug)]
+pub struct Given {
+    pub value: Option<f64>,
+    pub unit: String,
+    pub raw: String,
+    pub tolerance: Option<Tolerance>,
+}
+impl Given {
+    pub fn new(input: &str, calculation: Calculation) -> Result<Given, &'static str> {
+        let (_, input) = split_given(input);
+        let (value_str, tolerance_str) = if let Some(position) = input.find('±') {
+            (&input[..position], Some(&input[position + '±'.len_utf8()..]))
+        } else if let Some(position) = input.find("+-") {
+            (&input[..position], Some(&input[position + 2..]))
+        } else {
+            (&input[..], None)
+        };
+        let mut given = Given {
+            value: None,
+            unit: "".to_string(),
+            raw: value_str.trim().to_string(),
+            tolerance: None,
+        };
+        for unit in calculation.units().iter() {
+            if let Ok(value) = parse_value(value_str, &[unit]) {
+                given.value = Some(value);
+                given.unit = unit.to_string();
+                break;
+            }
+        }
+        // a material with a tolerance is turned into its specific resistance. without one it stays
+        // a material, so Elect still knows its temperature coefficient
+        if given.value.is_none() && tolerance_str.is_some() && calculation == Calculation::Elect {
+            if let Ok(elect) = Elect::new(vec![given.raw.clone()]) {
+                if let Some(rho) = elect.rho {
+                    given.value = Some(rho);
+                    given.unit = "ohmxmm2/m".to_string();
+                }
+            }
+        }
+        if let Some(tolerance_str) = tolerance_str {
+            let tolerance_str = tolerance_str.trim();
+            let (tolerance_str, sigma) = if tolerance_str.ends_with('σ') {
+                (&tolerance_str[..tolerance_str.len() - 'σ'.len_utf8()], true)
+            } else {
+                (tolerance_str, false)
+            };
+            let value = if let Some(value) = given.value {
+                value
+            } else {
+                return Err("only numbers and materials can have a tolerance");
+            };
+            let relative = if tolerance_str.ends_with('%') {
+                parse_value(tolerance_str, &["%"])? / 100f64
+            } else {
+                parse_value(tolerance_str, &[given.unit.as_str()])? / value
+            };
+            given.tolerance = Some(Tolerance {
+                relative: relative.abs(),
+                sigma,
+            });
+        }
+        Ok(given)
+    }
+    // the given value as string for Elect or URI, the number is multiplied with the factor
+    pub fn scaled(&self, factor: f64) -> String {
+        if let Some(value) = self.value {
+            format!("{}{}", value * factor, self.unit)
+        } else {
+            self.raw.clone()
+        }
+    }
+}
+
+// the spread of a result
+#[derive(Clone, Copy, Debug)]
+pub struct Spread {
+    pub nominal: f64,
+    pub low: f64,  // worst case
+    pub high: f64, // worst case
+    pub rss: f64,  // absolute, a standard deviation if the inputs are standard deviations
+}
+
+// evaluates the calculation with the nominal values and with the tolerances. for the worst case
+// every corner is calculated, so this is only for a few values with a tolerance
+pub fn propagate(
+    calculation: Calculation,
+    given: &[Given],
+) -> Result<(usize, Spread), &'static str> {
+    let strings = |factors: &Vec<f64>| -> Vec<String> {
+        given
+            .iter()
+            .zip(factors.iter())
+            .map(|(given, factor)| given.scaled(*factor))
+            .collect()
+    };
+    let ones: Vec<f64> = vec![1f64; given.len()];
+    let (index, nominal) = if let Some(result) = calculation.evaluate(strings(&ones)) {
+        result
+    } else {
+        return Err("the calculation has no result");
+    };
+    let toleranced: Vec<usize> = (0..given.len())
+        .filter(|k| given[*k].tolerance.is_some())
+        .collect();
+    let band = |k: usize| given[k].tolerance.map_or(0f64, |tolerance| tolerance.band());
+    let mut low = nominal;
+    let mut high = nominal;
+    for corner in 0..(1usize << toleranced.len()) {
+        let mut factors = ones.clone();
+        for (bit, k) in toleranced.iter().enumerate() {
+            factors[*k] = if corner & (1 << bit) != 0 {
+                1f64 + band(*k)
+            } else {
+                1f64 - band(*k)
+            };
+        }
+        // a corner without a result (like a negative length) would make the range too small
+        match calculation.evaluate(strings(&factors)) {
+            Some((_, value)) => {
+                low = low.min(value);
+                high = high.max(value);
+            }
+            None => return Err("the tolerances reach values without a result"),
+        }
+    }
+    // every value alone on both sides, half of the difference is its contribution
+    let mut sum_square = 0f64;
+    for k in toleranced.iter() {
+        let relative = given[*k].tolerance.map_or(0f64, |tolerance| tolerance.relative);
+        let mut factors = ones.clone();
+        factors[*k] = 1f64 + relative;
+        let plus = calculation.evaluate(strings(&factors));
+        factors[*k] = 1f64 - relative;
+        let minus = calculation.evaluate(strings(&factors));
+        if let (Some((_, plus)), Some((_, minus))) = (plus, minus) {
+            sum_square += ((plus - minus) / 2f64).powi(2);
+        }
+    }
+    Ok((
+        index,
+        Spread {
+            nominal,
+            low,
+            high,
+            rss: sum_square.sqrt(),
+        },
+    ))
+}
+
+#[derive(Clone, Debug)]
+pub struct ToleranceCalc {
+    calculation: Calculation,
+    given: Vec<Given>,
+    result: Option<(usize, Spread)>,
+}
+impl ToleranceCalc {
+    pub fn new(s: Vec<String>, calculation: Calculation) -> Result<ToleranceCalc, &'static str> {
+        let mut given: Vec<Given> = Vec::new();
+        for i in s.iter() {
+            given.push(Given::new(i, calculation)?);
+        }
+        Ok(ToleranceCalc {
+            calculation,
+            given,
+            result: None,
+        })
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        self.result = Some(propagate(self.calculation, &self.given)?);
+        if should_print {
+            self.print_res()
+        }
+        Ok(())
+    }
+    fn print_res(&self) {
+        if let Some((index, spread)) = self.result {
+            let (name, unit) = self.calculation.result_name(index);
+            let worst = (spread.high - spread.nominal)
+                .abs()
+                .max((spread.nominal - spread.low).abs());
+            let sigma = self
+                .given
+                .iter()
+                .any(|given| given.tolerance.is_some_and(|tolerance| tolerance.sigma));
+            println!(
+                "{} = {} {} ± {:.2} % (worst case)",
+                name,
+                spread.nominal,
+                unit,
+                worst / spread.nominal.abs() * 100f64
+            );
+            println!(
+                "the worst case range is: {} {} to {} {}",
+                spread.low, unit, spread.high, unit
+            );
+            println!(
+                "{} = {} {} ± {:.2} % ({})",
+                name,
+                spread.nominal,
+                unit,
+                spread.rss / spread.nominal.abs() * 100f64,
+                if sigma { "RSS, 1σ" } else { "RSS" }
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn given(inputs: &[&str], calculation: Calculation) -> Vec<Given> {
+        inputs.iter().map(|input| Given::new(input, calculation).unwrap()).collect()
+    }
+
+    #[test]
+    fn given_tolerances() {
+        let given = given(&["12V±0.5V", "4.7kohm+-5%", "1A±1%σ"], Calculation::Uri);
+        assert!((given[0].tolerance.unwrap().relative - 0.5f64 / 12f64).abs() < 1e-12);
+        assert_eq!(given[1].value, Some(4700f64));
+        assert!((given[1].tolerance.unwrap().band() - 0.05f64).abs() < 1e-12);
+        assert!(given[2].tolerance.unwrap().sigma);
+        assert!((given[2].tolerance.unwrap().band() - 0.03f64).abs() < 1e-12);
+        assert!(Given::new("copper±1%", Calculation::Uri).is_err());
+        assert!(Given::new("copper±1%", Calculation::Elect).unwrap().value.is_some());
+    }
+
+    #[test]
+    fn worst_case_and_rss() {
+        let given = given(&["u=10V±10%", "r=100ohm±10%"], Calculation::Uri);
+        let (index, spread) = propagate(Calculation::Uri, &given).unwrap();
+        assert_eq!(Calculation::Uri.result_name(index), ("I", "A"));
+        assert!((spread.nominal - 0.1f64).abs() < 1e-12);
+        assert!((spread.low - 9f64 / 110f64).abs() < 1e-12);
+        assert!((spread.high - 11f64 / 90f64).abs() < 1e-12);
+        let r_part = (10f64 / 90f64 - 10f64 / 110f64) / 2f64;
+        assert!((spread.rss - (0.01f64.powi(2) + r_part.powi(2)).sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn no_result() {
+        let alone = given(&["u=10V±10%"], Calculation::Uri);
+        assert!(propagate(Calculation::Uri, &alone).is_err());
+        // the lower corner of 3σ = 120 % is a negative voltage
+        let negative = given(&["u=10V±40%σ", "r=1ohm"], Calculation::Uri);
+        assert!(propagate(Calculation::Uri, &negative).is_err());
+    }
+}
@@ -0,0 +1,366 @@
+// transformers: the ideal one has the turns ratio a = N1 / N2 = U1 / U2 = I2 / I1 and shows the
+// load at the primary side as Z1 = a² * Z2. the real one loses voltage and power in the resistance
+// of its windings, calculated with Elect and copper, and in its core. the core is sized with the
+// area product, the window area Aw times the core area Ac:
+//   Ap = Aw * Ac = St / (Kf * Ku * B * f * J)    with St = S * (1 + 1 / eta) of both windings
+// Kf is 4.44 for sine and 4 for square wave, Ku the part of the window filled with copper, B the
+// flux density, f the frequency and J the current density in the wire. the turns follow from
+//   N = U / (Kf * f * B * Ac)
+use std::f64;
+
+use super::{format_value, parse_value, split_given, Elect};
+
+// the flux density in T a core material is designed with
+fn core_flux_density(material: &str) -> Option<f64> {
+    match material {
+        "steel" | "silicon-steel" | "electrical-steel" | "trafoblech" => Some(1.2f64),
+        "amorphous" => Some(1f64),
+        "nanocrystalline" => Some(0.8f64),
+        "powder" | "iron-powder" => Some(0.5f64),
+        "ferrite" => Some(0.2f64),
+        _ => None,
+    }
+}
+
+// the resistance of a copper winding of the turns with the mean length of a turn in m and the
+// area of the wire in mm²
+fn winding_resistance(
+    turns: f64,
+    mean_turn: f64,
+    area: f64,
+    temperature: Option<f64>,
+) -> Result<f64, &'static str> {
+    let mut values = vec![
+        "copper".to_string(),
+        format!("{}m", turns * mean_turn),
+        format!("{}mm2", area),
+    ];
+    if let Some(temperature) = temperature {
+        values.push(format!("{}°C", temperature));
+    }
+    let mut elect = Elect::new(values)?;
+    elect.calc_resistor();
+    match elect.resistor {
+        Some(resistor) => Ok(resistor),
+        None => Err("the resistance of the winding is not calculable"),
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Transformer {
+    u1: Option<f64>,    //V
+    u2: Option<f64>,    //V at no load
+    n1: Option<f64>,
+    n2: Option<f64>,
+    ratio: Option<f64>, //N1 / N2
+    load: Option<f64>,  //ohm at the secondary
+    power: Option<f64>, //VA at the secondary
+    power_factor: f64,
+    r1: Option<f64>,       //ohm
+    r2: Option<f64>,       //ohm
+    current_density: f64,  //A/mm²
+    flux_density: Option<f64>, //T
+    frequency: f64,        //Hz
+    form_factor: f64,      //Kf
+    fill: f64,             //Ku
+    core_area: Option<f64>, //m²
+    mean_turn: Option<f64>, //m
+    core_loss: f64,        //W
+    material: String,
+    temperature: Option<f64>, //°C of the windings
+    pub i1: Option<f64>,       //A
+    pub i2: Option<f64>,       //A
+    pub reflected: Option<f64>, //ohm the load at the primary side
+    pub efficiency: Option<f64>,
+    pub regulation: Option<f64>, //%
+}
+impl Transformer {
+    pub fn new(s: Vec<String>) -> Result<Transformer, &'static str> {
+        let mut transformer = Transformer {
+            u1: None,
+            u2: None,
+            n1: None,
+            n2: None,
+            ratio: None,
+            load: None,
+            power: None,
+            power_factor: 1f64,
+            r1: None,
+            r2: None,
+            current_density: 3f64,
+            flux_density: None,
+            frequency: 50f64,
+            form_factor: 4.44f64,
+            fill: 0.4f64,
+            core_area: None,
+            mean_turn: None,
+            core_loss: 0f64,
+            material: "steel".to_string(),
+            temperature: None,
+            i1: None,
+            i2: None,
+            reflected: None,
+            efficiency: None,
+            regulation: None,
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "u1" | "up" | "primary" => transformer.u1 = Some(parse_value(&value, &["V"])?),
+                "u2" | "us" | "secondary" => transformer.u2 = Some(parse_value(&value, &["V"])?),
+                "n1" => transformer.n1 = Some(parse_value(&value, &[])?),
+                "n2" => transformer.n2 = Some(parse_value(&value, &[])?),
+                "ratio" | "a" => transformer.ratio = Some(parse_value(&value, &[])?),
+                "rl" | "load" => transformer.load = Some(parse_value(&value, &["ohm", "Ω"])?),
+                "s" | "p" | "power" => transformer.power = Some(parse_value(&value, &["VA", "W"])?),
+                "pf" | "cos" => transformer.power_factor = parse_value(&value, &[])?,
+                "r1" => transformer.r1 = Some(parse_value(&value, &["ohm", "Ω"])?),
+                "r2" => transformer.r2 = Some(parse_value(&value, &["ohm", "Ω"])?),
+                "j" => transformer.current_density = parse_value(&value, &["A/mm2", "A/mm²"])?,
+                "b" => transformer.flux_density = Some(parse_value(&value, &["T"])?),
+                "f" => transformer.frequency = parse_value(&value, &["Hz"])?,
+                "ku" | "fill" => transformer.fill = parse_value(&value, &[])?,
+                "ac" | "core" => {
+                    transformer.core_area = Some(parse_value(&value, &["mm2", "mm²"])? * 1e-6f64)
+                }
+                "mlt" => transformer.mean_turn = Some(parse_value(&value, &["m"])?),
+                "pfe" | "core-loss" => transformer.core_loss = parse_value(&value, &["W"])?,
+                "t" | "temperature" => {
+                    transformer.temperature = Some(parse_value(&value, &["°C", "degC"])?)
+                }
+                "material" | "mat" => transformer.material = value.to_lowercase(),
+                "" => match value.to_lowercase().as_str() {
+                    "sine" | "sinus" => transformer.form_factor = 4.44f64,
+                    "square" | "rectangle" => transformer.form_factor = 4f64,
+                    material => transformer.material = material.to_string(),
+                },
+                _ => return Err("input is strange"),
+            }
+        }
+        if core_flux_density(&transformer.material).is_none() {
+            return Err("core material not known");
+        }
+        // the ratio from the turns, the given ratio or the voltages
+        transformer.ratio = match (transformer.n1, transformer.n2, transformer.ratio) {
+            (Some(n1), Some(n2), None) => Some(n1 / n2),
+            (Some(_), Some(_), Some(_)) => return Err("give me either the turns or the ratio"),
+            (_, _, Some(ratio)) => Some(ratio),
+            _ => match (transformer.u1, transformer.u2) {
+                (Some(u1), Some(u2)) => Some(u1 / u2),
+                _ => return Err("give me the voltages u1 and u2, the turns n1 and n2 or the ratio"),
+            },
+        };
+        let ratio = transformer.ratio.unwrap_or(f64::NAN);
+        if !(ratio > 0f64 && ratio.is_finite()) {
+            return Err("the ratio has to be positive");
+        }
+        match (transformer.u1, transformer.u2) {
+            (Some(_), Some(_)) if transformer.n1.is_some() && transformer.n2.is_some() => {
+                return Err("give me either the voltages or the turns")
+            }
+            (Some(u1), None) => transformer.u2 = Some(u1 / ratio),
+            (None, Some(u2)) => transformer.u1 = Some(u2 * ratio),
+            _ => {}
+        }
+        if transformer.load.is_some() && transformer.power.is_some() {
+            return Err("give me either the load or the power");
+        }
+        // the load alone is reflected with the ratio, the power needs the voltage for the currents
+        if transformer.power.is_some() && transformer.u1.is_none() {
+            return Err("give me a voltage for the power");
+        }
+        if !(transformer.power_factor > 0f64 && transformer.power_factor <= 1f64) {
+            return Err("the power factor has to be between 0 and 1");
+        }
+        if transformer.current_density <= 0f64
+            || transformer.frequency <= 0f64
+            || !(transformer.fill > 0f64 && transformer.fill <= 1f64)
+        {
+            return Err("the current density, frequency and fill factor have to be positive");
+        }
+        Ok(transformer)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        let ratio = self.ratio.unwrap_or(f64::NAN);
+        let u1 = self.u1.unwrap_or(f64::NAN);
+        let u2 = self.u2.unwrap_or(f64::NAN);
+        let flux_density = match self.flux_density {
+            Some(flux_density) => flux_density,
+            None => core_flux_density(&self.material).unwrap_or_default(),
+        };
+        // the currents of the load, the magnetizing current is left out
+        self.i2 = match (self.load, self.power) {
+            (Some(load), _) if load > 0f64 => {
+                self.reflected = Some(load * ratio * ratio);
+                self.u2.map(|u2| u2 / load)
+            }
+            (_, Some(power)) if power > 0f64 => Some(power / u2),
+            (None, None) => None,
+            _ => return Err("the load and the power have to be positive"),
+        };
+        self.i1 = self.i2.map(|i2| i2 / ratio);
+        // the core of the area product, with an efficiency of 90 % for the primary winding
+        let mut core = None;
+        if let Some(i2) = self.i2 {
+            let apparent = u2 * i2 * (1f64 + 1f64 / 0.9f64);
+            let area_product = apparent
+                / (self.form_factor * self.fill * flux_density * self.frequency
+                    * self.current_density
+                    * 1e6f64); //m⁴
+            // a core with a square window as large as its cross section
+            let core_area = if let Some(core_area) = self.core_area {
+                core_area
+            } else {
+                area_product.sqrt()
+            };
+            if self.n1.is_none() {
+                let n1 = (u1 / (self.form_factor * self.frequency * flux_density * core_area))
+                    .ceil();
+                self.n1 = Some(n1);
+                self.n2 = Some((n1 / ratio).ceil());
+            }
+            core = Some((area_product, core_area));
+        }
+        // the windings with the wire of the current density around the core
+        if let (Some(i1), Some(i2), Some(n1), Some(n2)) = (self.i1, self.i2, self.n1, self.n2) {
+            let core_area = match (self.core_area, core) {
+                (Some(core_area), _) => Some(core_area),
+                (None, Some((_, core_area))) => Some(core_area),
+                _ => None,
+            };
+            // the mean turn around a square core leg with the winding on it
+            let mean_turn = match (self.mean_turn, core_area) {
+                (Some(mean_turn), _) => Some(mean_turn),
+                (None, Some(core_area)) => {
+                    Some((4f64 + f64::consts::PI / 2f64) * core_area.sqrt())
+                }
+                _ => None,
+            };
+            if let Some(mean_turn) = mean_turn {
+                if self.r1.is_none() {
+                    let area = i1 / self.current_density;
+                    self.r1 = Some(winding_resistance(n1, mean_turn, area, self.temperature)?);
+                }
+                if self.r2.is_none() {
+                    let area = i2 / self.current_density;
+                    self.r2 = Some(winding_resistance(n2, mean_turn, area, self.temperature)?);
+                }
+            }
+        }
+        // the regulation and efficiency with the resistances referred to the secondary side
+        if let (Some(i2), Some(r1), Some(r2)) = (self.i2, self.r1, self.r2) {
+            let resistance = r1 / (ratio * ratio) + r2;
+            let loaded = u2 - i2 * resistance * self.power_factor;
+            let output = loaded * i2 * self.power_factor;
+            let copper = i2 * i2 * resistance;
+            self.regulation = Some((u2 - loaded) / loaded * 100f64);
+            self.efficiency = Some(output / (output + copper + self.core_loss));
+        }
+        if should_print {
+            self.print_res(flux_density, core)
+        }
+        Ok(())
+    }
+    fn print_res(&self, flux_density: f64, core: Option<(f64, f64)>) {
+        let ratio = self.ratio.unwrap_or(0f64);
+        let u1 = self.u1.unwrap_or(f64::NAN);
+        let u2 = self.u2.unwrap_or(f64::NAN);
+        println!("the turns ratio N1:N2 is {:.4}:1", ratio);
+        if !u1.is_nan() {
+            println!("U1 = {}, U2 = {} at no load", format_value(u1, "V"), format_value(u2, "V"));
+        }
+        if let (Some(load), Some(reflected)) = (self.load, self.reflected) {
+            println!(
+                "the load of {} is {} at the primary side",
+                format_value(load, "Ω"),
+                format_value(reflected, "Ω")
+            );
+        }
+        if let (Some(i1), Some(i2)) = (self.i1, self.i2) {
+            println!(
+                "I1 = {}, I2 = {}, S = {}",
+                format_value(i1, "A"),
+                format_value(i2, "A"),
+                format_value(u2 * i2, "VA")
+            );
+        }
+        if let Some((area_product, core_area)) = core {
+            println!(
+                "the {} core at {} and {} needs Ap = {:.3} cm⁴, Ac = {:.3} cm²",
+                self.material,
+                format_value(flux_density, "T"),
+                format_value(self.frequency, "Hz"),
+                area_product * 1e8f64,
+                core_area * 1e4f64
+            );
+        }
+        if let (Some(n1), Some(n2)) = (self.n1, self.n2) {
+            println!("N1 = {}, N2 = {}", n1, n2);
+            if let (Some(i1), Some(i2)) = (self.i1, self.i2) {
+                println!(
+                    "the wires at {} A/mm² have {:.4} mm² and {:.4} mm²",
+                    self.current_density,
+                    i1 / self.current_density,
+                    i2 / self.current_density
+                );
+            }
+        }
+        if let (Some(r1), Some(r2)) = (self.r1, self.r2) {
+            println!("R1 = {}, R2 = {}", format_value(r1, "Ω"), format_value(r2, "Ω"));
+        }
+        if let (Some(i1), Some(i2), Some(r1), Some(r2)) = (self.i1, self.i2, self.r1, self.r2) {
+            println!(
+                "the copper losses are {} + {} = {}, the core loss is {}",
+                format_value(i1 * i1 * r1, "W"),
+                format_value(i2 * i2 * r2, "W"),
+                format_value(i1 * i1 * r1 + i2 * i2 * r2, "W"),
+                format_value(self.core_loss, "W")
+            );
+        }
+        if let (Some(regulation), Some(efficiency)) = (self.regulation, self.efficiency) {
+            println!(
+                "at the load U2 drops to {} (regulation {:.2} %), the efficiency is {:.2} %",
+                format_value(u2 / (1f64 + regulation / 100f64), "V"),
+                regulation,
+                efficiency * 100f64
+            );
+        }
+        if self.material == "ferrite" && self.frequency < 1000f64 {
+            println!("note: ferrite is made for high frequencies, give me f");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::calculated;
+
+    fn transformer(given: &[&str]) -> Transformer {
+        calculated(given, Transformer::new, Transformer::calc).unwrap()
+    }
+
+    #[test]
+    fn reflected_load_without_voltage() {
+        let transformer = transformer(&["ratio=10", "rl=2ohm"]);
+        assert!((transformer.reflected.unwrap() - 200f64).abs() < 1e-12);
+        assert_eq!(transformer.i2, None);
+    }
+
+    #[test]
+    fn ideal_currents() {
+        let transformer = transformer(&["u1=230V", "u2=23V", "rl=2.3ohm", "r1=0ohm", "r2=0ohm"]);
+        assert!((transformer.i2.unwrap() - 10f64).abs() < 1e-12);
+        assert!((transformer.i1.unwrap() - 1f64).abs() < 1e-12);
+        assert!((transformer.efficiency.unwrap() - 1f64).abs() < 1e-12);
+        assert!(transformer.regulation.unwrap().abs() < 1e-12);
+    }
+
+    #[test]
+    fn turns_of_the_core() {
+        // N = U / (4.44 f B Ac) with 1000 mm² at 1.2 T
+        let transformer = transformer(&["u1=230V", "u2=12V", "s=50VA", "ac=1000mm2"]);
+        let n1 = (230f64 / (4.44f64 * 50f64 * 1.2f64 * 1e-3f64)).ceil();
+        assert_eq!(transformer.n1, Some(n1));
+    }
+}
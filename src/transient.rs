@@ -0,0 +1,463 @@
+// the behaviour of RC, RL and RLC circuits over the time after switching. like in URI two of the
+// component values (or the time constant) are enough, the rest is calculated
+use std::f64;
+
+use super::{parse_value, split_given};
+
+// charging means the source is switched on, discharging means it is switched off (or shorted)
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Switching {
+    Charge,
+    Discharge,
+}
+
+// first order circuits: RC with τ = R·C and RL with τ = L/R
+#[derive(Clone, Debug)]
+pub struct FirstOrder {
+    switching: Switching,
+    r: Option<f64>,           //ohm
+    c: Option<f64>,           //F
+    l: Option<f64>,           //H
+    tau: Option<f64>,         //s
+    u_source: f64,            //V
+    u_start: Option<f64>,     //V across the capacitor at t = 0
+    u_threshold: Option<f64>, //V
+    t: Option<f64>,           //s
+    t_threshold: Option<f64>, //s
+    samples: usize,
+}
+impl FirstOrder {
+    pub fn new(s: Vec<String>) -> Result<FirstOrder, &'static str> {
+        let mut first_order = FirstOrder {
+            switching: Switching::Charge,
+            r: None,
+            c: None,
+            l: None,
+            tau: None,
+            u_source: 0f64,
+            u_start: None,
+            u_threshold: None,
+            t: None,
+            t_threshold: None,
+            samples: 0,
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "r" => first_order.r = Some(parse_value(&value, &["ohm", "Ω"])?),
+                "c" => first_order.c = Some(parse_value(&value, &["F"])?),
+                "l" => first_order.l = Some(parse_value(&value, &["H"])?),
+                "tau" | "τ" => first_order.tau = Some(parse_value(&value, &["s"])?),
+                "u" => first_order.u_source = parse_value(&value, &["V"])?,
+                "u0" => first_order.u_start = Some(parse_value(&value, &["V"])?),
+                "uth" => first_order.u_threshold = Some(parse_value(&value, &["V"])?),
+                "t" => first_order.t = Some(parse_value(&value, &["s"])?),
+                "samples" => {
+                    first_order.samples = if let Ok(n) = value.parse::<usize>() {
+                        n
+                    } else {
+                        return Err("number of samples not parseable");
+                    }
+                }
+                "" => {
+                    first_order.switching = match value.to_lowercase().as_str() {
+                        "charge" | "laden" | "on" => Switching::Charge,
+                        "discharge" | "entladen" | "off" => Switching::Discharge,
+                        _ => return Err("switching not known"),
+                    }
+                }
+                _ => return Err("input is strange"),
+            }
+        }
+        if first_order.c.is_some() && first_order.l.is_some() {
+            return Err("with C and L use the RLC calculation");
+        }
+        // like in URI the components have to be positive, the curves would be NaN and inf
+        let components = [first_order.r, first_order.c, first_order.l, first_order.tau];
+        if components.iter().any(|value| value.is_some_and(|value| value <= 0f64)) {
+            return Err("R, C, L and tau have to be positive");
+        }
+        Ok(first_order)
+    }
+    fn is_rl(&self) -> bool {
+        self.l.is_some()
+    }
+    // the final voltage across the capacitor. for the RL circuit the same curve is used for the
+    // current, with the final value U/R
+    fn final_value(&self) -> f64 {
+        match self.switching {
+            Switching::Charge => self.u_source,
+            Switching::Discharge => 0f64,
+        }
+    }
+    // the voltage across the capacitor at t = 0. without u0 the capacitor is empty before it is
+    // charged and full before it is discharged, like the current of the RL circuit
+    fn start_voltage(&self) -> f64 {
+        match (self.u_start, self.switching) {
+            (Some(u_start), _) => u_start,
+            (None, Switching::Charge) => 0f64,
+            (None, Switching::Discharge) => self.u_source,
+        }
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        // give two, get the rest
+        if self.is_rl() {
+            match (self.r, self.l, self.tau) {
+                (Some(r), Some(l), None) if r > 0f64 => self.tau = Some(l / r),
+                (Some(r), None, Some(tau)) => self.l = Some(tau * r),
+                (None, Some(l), Some(tau)) if tau > 0f64 => self.r = Some(l / tau),
+                (Some(_), Some(_), Some(_)) => {}
+                _ => return Err("give me two of R, L and tau"),
+            }
+        } else {
+            match (self.r, self.c, self.tau) {
+                (Some(r), Some(c), None) => self.tau = Some(r * c),
+                (Some(r), None, Some(tau)) if r > 0f64 => self.c = Some(tau / r),
+                (None, Some(c), Some(tau)) if c > 0f64 => self.r = Some(tau / c),
+                (Some(_), Some(_), Some(_)) => {}
+                _ => return Err("give me two of R, C and tau"),
+            }
+        }
+        if let Some(u_threshold) = self.u_threshold {
+            self.t_threshold = self.time_to(u_threshold);
+            if self.t_threshold.is_none() {
+                return Err("the threshold is never reached");
+            }
+        }
+        if should_print {
+            self.print_res()
+        }
+        Ok(())
+    }
+    // u(t) = U_end + (U0 - U_end)·e^(-t/τ)
+    fn value_at(&self, t: f64) -> f64 {
+        let tau = self.tau.unwrap_or(f64::NAN);
+        let start = if self.is_rl() { self.start_current() } else { self.start_voltage() };
+        let end = if self.is_rl() { self.final_current() } else { self.final_value() };
+        end + (start - end) * (-t / tau).exp()
+    }
+    // the current through the resistor. for the RC circuit it is (U_end - u(t))/R
+    fn current_at(&self, t: f64) -> f64 {
+        if self.is_rl() {
+            self.value_at(t)
+        } else {
+            let r = self.r.unwrap_or(f64::NAN);
+            (self.final_value() - self.value_at(t)) / r
+        }
+    }
+    fn start_current(&self) -> f64 {
+        match self.switching {
+            Switching::Charge => 0f64,
+            Switching::Discharge => {
+                let r = self.r.unwrap_or(f64::NAN);
+                self.u_source / r
+            }
+        }
+    }
+    fn final_current(&self) -> f64 {
+        let r = self.r.unwrap_or(f64::NAN);
+        self.final_value() / r
+    }
+    // t = -τ·ln((U_end - U_th) / (U_end - U0)), only if the threshold lies between start and end
+    fn time_to(&self, threshold: f64) -> Option<f64> {
+        let tau = self.tau.unwrap_or(f64::NAN);
+        let start = if self.is_rl() { self.start_current() } else { self.start_voltage() };
+        let end = if self.is_rl() { self.final_current() } else { self.final_value() };
+        // for the RL circuit the threshold is given as voltage across the resistor
+        let threshold = if self.is_rl() {
+            let r = self.r.unwrap_or(f64::NAN);
+            threshold / r
+        } else {
+            threshold
+        };
+        let ratio = (end - threshold) / (end - start);
+        if ratio > 0f64 && ratio <= 1f64 && !tau.is_nan() {
+            Some(-tau * ratio.ln())
+        } else {
+            None
+        }
+    }
+    fn print_res(&self) {
+        let tau = self.tau.unwrap_or(0f64);
+        let r = self.r.unwrap_or(0f64);
+        if self.is_rl() {
+            let l = self.l.unwrap_or(0f64);
+            println!(
+                "The time constant of the RL circuit with R = {} Ω and L = {} H is: {} s",
+                r, l, tau
+            );
+        } else {
+            let c = self.c.unwrap_or(0f64);
+            println!(
+                "The time constant of the RC circuit with R = {} Ω and C = {} F is: {} s",
+                r, c, tau
+            );
+        }
+        if let Some(t) = self.t {
+            if self.is_rl() {
+                println!("The current after {} s is: {} A", t, self.value_at(t));
+            } else {
+                println!(
+                    "The capacitor voltage after {} s is: {} V and the current is: {} A",
+                    t,
+                    self.value_at(t),
+                    self.current_at(t)
+                );
+            }
+        }
+        if let (Some(u_threshold), Some(t_threshold)) = (self.u_threshold, self.t_threshold) {
+            println!("The time to reach {} V is: {} s", u_threshold, t_threshold);
+        }
+        if self.samples > 0 {
+            // the table goes from 0 to 5τ, after that the curve is at more than 99 %
+            if self.is_rl() {
+                println!("{:>14} | {:>14} | {:>14}", "t in s", "i in A", "u_L in V");
+            } else {
+                println!("{:>14} | {:>14} | {:>14}", "t in s", "u_C in V", "i in A");
+            }
+            for k in 0..self.samples + 1 {
+                let t = 5f64 * tau * k as f64 / self.samples as f64;
+                if self.is_rl() {
+                    // u_L = U_end - i·R
+                    println!(
+                        "{:>14.6e} | {:>14.6e} | {:>14.6e}",
+                        t,
+                        self.current_at(t),
+                        self.final_value() - self.current_at(t) * r
+                    );
+                } else {
+                    println!(
+                        "{:>14.6e} | {:>14.6e} | {:>14.6e}",
+                        t,
+                        self.value_at(t),
+                        self.current_at(t)
+                    );
+                }
+            }
+        }
+    }
+}
+
+// the series RLC circuit as second order system
+#[derive(Clone, Debug, Default)]
+pub struct SecondOrder {
+    r: Option<f64>,    //ohm
+    l: Option<f64>,    //H
+    c: Option<f64>,    //F
+    f0: Option<f64>,   //Hz natural frequency
+    zeta: Option<f64>, //damping ratio
+    u_source: f64,     //V step
+    samples: usize,
+}
+impl SecondOrder {
+    pub fn new(s: Vec<String>) -> Result<SecondOrder, &'static str> {
+        let mut second_order: SecondOrder = Default::default();
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "r" => second_order.r = Some(parse_value(&value, &["ohm", "Ω"])?),
+                "l" => second_order.l = Some(parse_value(&value, &["H"])?),
+                "c" => second_order.c = Some(parse_value(&value, &["F"])?),
+                "f0" => second_order.f0 = Some(parse_value(&value, &["Hz"])?),
+                "zeta" | "ζ" | "d" => second_order.zeta = Some(parse_value(&value, &[])?),
+                "u" => second_order.u_source = parse_value(&value, &["V"])?,
+                "samples" => {
+                    second_order.samples = if let Ok(n) = value.parse::<usize>() {
+                        n
+                    } else {
+                        return Err("number of samples not parseable");
+                    }
+                }
+                _ => return Err("input is strange"),
+            }
+        }
+        let components = [
+            second_order.r,
+            second_order.l,
+            second_order.c,
+            second_order.f0,
+            second_order.zeta,
+        ];
+        if components.iter().any(|value| value.is_some_and(|value| value <= 0f64)) {
+            return Err("R, L, C, f0 and zeta have to be positive");
+        }
+        Ok(second_order)
+    }
+    // like in the three phase calculation we try every rule as long as we find something new.
+    // ω0 = 1/√(L·C) and ζ = R/2·√(C/L)
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        let two_pi = 2f64 * f64::consts::PI;
+        let mut changed = true;
+        while changed {
+            changed = false;
+            if self.f0.is_none() {
+                if let (Some(l), Some(c)) = (self.l, self.c) {
+                    self.f0 = Some(1f64 / (two_pi * (l * c).sqrt()));
+                    changed = true;
+                }
+            }
+            if self.c.is_none() {
+                if let (Some(l), Some(f0)) = (self.l, self.f0) {
+                    self.c = Some(1f64 / ((two_pi * f0).powi(2) * l));
+                    changed = true;
+                }
+            }
+            if self.l.is_none() {
+                if let (Some(c), Some(f0)) = (self.c, self.f0) {
+                    self.l = Some(1f64 / ((two_pi * f0).powi(2) * c));
+                    changed = true;
+                }
+            }
+            if self.zeta.is_none() {
+                if let (Some(r), Some(l), Some(c)) = (self.r, self.l, self.c) {
+                    self.zeta = Some(r / 2f64 * (c / l).sqrt());
+                    changed = true;
+                }
+            }
+            if self.r.is_none() {
+                if let (Some(zeta), Some(l), Some(c)) = (self.zeta, self.l, self.c) {
+                    self.r = Some(2f64 * zeta * (l / c).sqrt());
+                    changed = true;
+                }
+            }
+        }
+        if self.f0.is_none() || self.zeta.is_none() {
+            return Err("give me L and C (or f0 and one of them) and R or zeta");
+        }
+        if should_print {
+            self.print_res()
+        }
+        Ok(())
+    }
+    fn omega0(&self) -> f64 {
+        2f64 * f64::consts::PI * self.f0.unwrap_or(f64::NAN)
+    }
+    fn zeta(&self) -> f64 {
+        self.zeta.unwrap_or(f64::NAN)
+    }
+    // overshoot of the step response, only the underdamped circuit overshoots
+    fn overshoot(&self) -> f64 {
+        let zeta = self.zeta();
+        if zeta < 1f64 {
+            (-f64::consts::PI * zeta / (1f64 - zeta * zeta).sqrt()).exp()
+        } else {
+            0f64
+        }
+    }
+    // settling time to 2 % of the final value, the usual approximation 4/(ζ·ω0). for the
+    // overdamped circuit the slower of the two real poles is used
+    fn settling_time(&self) -> f64 {
+        let zeta = self.zeta();
+        let omega0 = self.omega0();
+        if zeta < 1f64 {
+            4f64 / (zeta * omega0)
+        } else {
+            4f64 / (omega0 * (zeta - (zeta * zeta - 1f64).sqrt()))
+        }
+    }
+    // the capacitor voltage of the step response
+    fn value_at(&self, t: f64) -> f64 {
+        let zeta = self.zeta();
+        let omega0 = self.omega0();
+        let u = self.u_source;
+        if zeta < 1f64 {
+            let omega_d = omega0 * (1f64 - zeta * zeta).sqrt();
+            u * (1f64
+                - (-zeta * omega0 * t).exp()
+                    * ((omega_d * t).cos() + zeta * omega0 / omega_d * (omega_d * t).sin()))
+        } else if (zeta - 1f64).abs() < 1e-9 {
+            u * (1f64 - (1f64 + omega0 * t) * (-omega0 * t).exp())
+        } else {
+            let root = (zeta * zeta - 1f64).sqrt();
+            let s1 = -omega0 * (zeta - root);
+            let s2 = -omega0 * (zeta + root);
+            u * (1f64 + (s2 * (s1 * t).exp() - s1 * (s2 * t).exp()) / (s1 - s2))
+        }
+    }
+    fn print_res(&self) {
+        let r = self.r.unwrap_or(0f64);
+        let l = self.l.unwrap_or(0f64);
+        let c = self.c.unwrap_or(0f64);
+        let f0 = self.f0.unwrap_or(0f64);
+        let zeta = self.zeta();
+        println!("The series RLC circuit with R = {} Ω, L = {} H and C = {} F has:", r, l, c);
+        println!("natural frequency f0: {} Hz (ω0 = {} 1/s)", f0, self.omega0());
+        println!("damping ratio ζ: {}", zeta);
+        if zeta < 1f64 {
+            println!(
+                "it is underdamped with the damped frequency: {} Hz",
+                f0 * (1f64 - zeta * zeta).sqrt()
+            );
+        } else if (zeta - 1f64).abs() < 1e-9 {
+            println!("it is critically damped");
+        } else {
+            println!("it is overdamped");
+        }
+        println!("overshoot: {} %", self.overshoot() * 100f64);
+        println!("settling time (2 %): {} s", self.settling_time());
+        if self.samples > 0 {
+            // the table goes up to the settling time and a bit more
+            let t_end = 1.5f64 * self.settling_time();
+            println!("{:>14} | {:>14}", "t in s", "u in V");
+            for k in 0..self.samples + 1 {
+                let t = t_end * k as f64 / self.samples as f64;
+                println!("{:>14.6e} | {:>14.6e}", t, self.value_at(t));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::{calculated, strings};
+
+    fn first_order(given: &[&str]) -> FirstOrder {
+        calculated(given, FirstOrder::new, FirstOrder::calc).unwrap()
+    }
+
+    #[test]
+    fn rc_charge() {
+        let rc = first_order(&["r=10kohm", "c=100uF", "u=12V"]);
+        assert!((rc.tau.unwrap() - 1f64).abs() < 1e-12);
+        // after one τ the capacitor is at 63.2 %
+        assert!((rc.value_at(1f64) - 12f64 * (1f64 - (-1f64).exp())).abs() < 1e-9);
+        assert!((rc.time_to(6f64).unwrap() - 2f64.ln()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rc_discharge_starts_at_u() {
+        let rc = first_order(&["discharge", "r=10kohm", "c=100uF", "u=12V"]);
+        assert!((rc.value_at(0f64) - 12f64).abs() < 1e-9);
+        assert!((rc.value_at(1f64) - 12f64 * (-1f64).exp()).abs() < 1e-9);
+        assert!((rc.current_at(0f64) + 1.2e-3f64).abs() < 1e-12);
+        let rc = first_order(&["discharge", "r=10kohm", "c=100uF", "u=12V", "u0=5V"]);
+        assert!((rc.value_at(0f64) - 5f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rl_time_constant() {
+        let rl = first_order(&["r=10ohm", "l=100mH", "u=10V"]);
+        assert!((rl.tau.unwrap() - 0.01f64).abs() < 1e-12);
+        assert!((rl.value_at(f64::INFINITY) - 1f64).abs() < 1e-12);
+    }
+
+    #[test]
+    fn rlc_frequency_and_damping() {
+        let rlc = calculated(&["r=10ohm", "l=1mH", "c=1uF"], SecondOrder::new, SecondOrder::calc)
+            .unwrap();
+        assert!((rlc.f0.unwrap() - 5032.92f64).abs() < 0.01f64);
+        assert!((rlc.zeta() - 5f64 * 1e-3f64.sqrt()).abs() < 1e-12);
+        assert!(rlc.overshoot() > 0f64 && rlc.overshoot() < 1f64);
+    }
+
+    #[test]
+    fn components_have_to_be_positive() {
+        assert!(FirstOrder::new(strings(&["r=0ohm", "c=1uF"])).is_err());
+        assert!(FirstOrder::new(strings(&["r=1kohm", "l=-1mH"])).is_err());
+        assert!(FirstOrder::new(strings(&["tau=0s", "c=1uF"])).is_err());
+        assert!(SecondOrder::new(strings(&["r=0ohm", "l=1mH", "c=1uF"])).is_err());
+        assert!(SecondOrder::new(strings(&["l=-1mH", "c=1uF"])).is_err());
+        assert!(SecondOrder::new(strings(&["f0=1kHz", "zeta=0"])).is_err());
+    }
+}
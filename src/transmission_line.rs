@@ -0,0 +1,351 @@
+// the wire as signal path: the characteristic impedance, the delay and the loss of coax, twisted
+// pair (a two wire line), microstrip and stripline from their geometry and the dielectric
+//   coax         Z0 = 60 / sqrt(er) * ln(D / d)
+//   two wire     Z0 = 120 / sqrt(er) * acosh(s / d)
+//   microstrip   Z0 = 87 / sqrt(er + 1.41) * ln(5.98 h / (0.8 w + t))          (IPC-2141)
+//   stripline    Z0 = 60 / sqrt(er) * ln(4 b / (0.67 pi (0.8 w + t)))
+// the loss of the conductor comes from rho of the material Elect knows and the skin effect, the
+// current only flows in the depth delta = sqrt(rho / (pi f mu0)) at high frequencies. the
+// dielectric adds its loss with the loss tangent
+use std::f64;
+
+use super::{format_value, parse_value, split_given, Elect};
+use decibel;
+use resistivity::{parse_thickness, OUNCE};
+
+// the speed of light in m/s and the magnetic constant in H/m
+const C0: f64 = 299792458f64;
+const MU0: f64 = 4e-7f64 * f64::consts::PI;
+// one neper in dB
+const NEPER: f64 = 8.685889638f64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Coax,
+    TwoWire,
+    Microstrip,
+    Stripline,
+}
+impl Kind {
+    fn new(name: &str) -> Option<Kind> {
+        match name.to_lowercase().as_str() {
+            "coax" | "koax" | "coaxial" => Some(Kind::Coax),
+            "twisted-pair" | "twisted" | "pair" | "two-wire" | "parallel" => Some(Kind::TwoWire),
+            "microstrip" => Some(Kind::Microstrip),
+            "stripline" => Some(Kind::Stripline),
+            _ => None,
+        }
+    }
+    fn name(&self) -> &'static str {
+        match self {
+            Kind::Coax => "coax",
+            Kind::TwoWire => "two wire line",
+            Kind::Microstrip => "microstrip",
+            Kind::Stripline => "stripline",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Line {
+    kind: Option<Kind>,
+    inner: Option<f64>,   //m diameter of the inner conductor of the coax or of the wires
+    outer: Option<f64>,   //m inner diameter of the shield
+    spacing: Option<f64>, //m between the centres of the two wires
+    width: Option<f64>,   //m of the strip
+    height: Option<f64>,  //m of the dielectric, for the stripline between the two planes
+    thickness: f64,       //m of the strip
+    er: Option<f64>,      //relative permittivity
+    tan_delta: f64,       //loss tangent of the dielectric
+    rho: f64,             //ohm*m of the conductor
+    frequency: Option<f64>, //Hz
+    length: Option<f64>,    //m
+    pub z0: Option<f64>,    //ohm
+    pub e_eff: Option<f64>, //effective permittivity
+}
+impl Line {
+    pub fn new(s: Vec<String>) -> Result<Line, &'static str> {
+        let mut line = Line {
+            kind: None,
+            inner: None,
+            outer: None,
+            spacing: None,
+            width: None,
+            height: None,
+            thickness: OUNCE / 1000f64,
+            er: None,
+            tan_delta: 0f64,
+            rho: 1.721e-8f64,
+            frequency: None,
+            length: None,
+            z0: None,
+            e_eff: None,
+        };
+        for i in s.iter() {
+            let (name, value) = split_given(i);
+            match name.as_str() {
+                "d" | "di" => line.inner = Some(parse_value(&value, &["m"])?),
+                "da" | "shield" => line.outer = Some(parse_value(&value, &["m"])?),
+                "s" | "spacing" => line.spacing = Some(parse_value(&value, &["m"])?),
+                "w" | "width" => line.width = Some(parse_value(&value, &["m"])?),
+                "height" | "b" => line.height = Some(parse_value(&value, &["m"])?),
+                "oz" | "h" | "thickness" => {
+                    line.thickness = match value.parse::<f64>() {
+                        Ok(ounces) if ounces > 0f64 => ounces * OUNCE,
+                        _ => parse_thickness(&value)?,
+                    } / 1000f64
+                }
+                "er" | "εr" => line.er = Some(parse_value(&value, &[])?),
+                "tand" | "tanδ" => line.tan_delta = parse_value(&value, &[])?,
+                "f" => line.frequency = Some(parse_value(&value, &["Hz"])?),
+                "l" | "length" => line.length = Some(parse_value(&value, &["m"])?),
+                // the conductor is a material of Elect
+                "material" | "mat" => line.rho = material_rho(&value)?,
+                "" => match Kind::new(&value) {
+                    Some(kind) => line.kind = Some(kind),
+                    None => line.rho = material_rho(&value)?,
+                },
+                _ => return Err("input is strange"),
+            }
+        }
+        let positive = |value: Option<f64>| value.is_some_and(|value| value > 0f64);
+        let kind = match line.kind {
+            Some(kind) => kind,
+            None => return Err("give me coax, twisted-pair, microstrip or stripline"),
+        };
+        let complete = match kind {
+            Kind::Coax => positive(line.inner) && positive(line.outer),
+            Kind::TwoWire => positive(line.inner) && positive(line.spacing),
+            Kind::Microstrip | Kind::Stripline => positive(line.width) && positive(line.height),
+        };
+        if !complete {
+            return Err(match kind {
+                Kind::Coax => "give me the diameters di and da of the coax",
+                Kind::TwoWire => "give me the wire diameter d and the spacing s",
+                _ => "give me the width w and the height of the dielectric",
+            });
+        }
+        // the usual dielectrics: polyethylene, pvc insulation and FR4
+        if line.er.is_none() {
+            line.er = Some(match kind {
+                Kind::Coax => 2.25f64,
+                Kind::TwoWire => 2f64,
+                _ => 4.4f64,
+            });
+        }
+        if !positive(line.er) || line.er < Some(1f64) {
+            return Err("the relative permittivity has to be 1 or more");
+        }
+        Ok(line)
+    }
+    pub fn calc(&mut self, should_print: bool) -> Result<(), &'static str> {
+        let er = self.er.unwrap_or(1f64);
+        let inner = self.inner.unwrap_or(f64::NAN);
+        let width = self.width.unwrap_or(f64::NAN);
+        let height = self.height.unwrap_or(f64::NAN);
+        let (z0, e_eff) = match self.kind {
+            Some(Kind::Coax) => {
+                let outer = self.outer.unwrap_or(f64::NAN);
+                if outer <= inner {
+                    return Err("the shield has to be larger than the inner conductor");
+                }
+                (60f64 / er.sqrt() * (outer / inner).ln(), er)
+            }
+            Some(Kind::TwoWire) => {
+                let spacing = self.spacing.unwrap_or(f64::NAN);
+                if spacing <= inner {
+                    return Err("the spacing has to be larger than the wire diameter");
+                }
+                (120f64 / er.sqrt() * (spacing / inner).acosh(), er)
+            }
+            Some(Kind::Microstrip) => {
+                let e_eff = (er + 1f64) / 2f64
+                    + (er - 1f64) / 2f64 / (1f64 + 12f64 * height / width).sqrt();
+                let z0 = 87f64 / (er + 1.41f64).sqrt()
+                    * (5.98f64 * height / (0.8f64 * width + self.thickness)).ln();
+                (z0, e_eff)
+            }
+            _ => {
+                let z0 = 60f64 / er.sqrt()
+                    * (4f64 * height
+                        / (0.67f64 * f64::consts::PI * (0.8f64 * width + self.thickness)))
+                        .ln();
+                (z0, er)
+            }
+        };
+        if z0.is_nan() || z0 <= 0f64 {
+            return Err("the geometry gives no impedance, the strip is too wide");
+        }
+        self.z0 = Some(z0);
+        self.e_eff = Some(e_eff);
+        if should_print {
+            self.print_res(z0, e_eff)
+        }
+        Ok(())
+    }
+    // the resistance per m of all conductors at the frequency, the larger one of the resistance
+    // with the whole cross section and the one in the skin depth
+    fn resistance(&self, frequency: f64) -> f64 {
+        let surface = (f64::consts::PI * frequency * MU0 * self.rho).sqrt(); //ohm
+        let skin_depth = (self.rho / (f64::consts::PI * frequency * MU0)).sqrt();
+        let inner = self.inner.unwrap_or(f64::NAN);
+        let width = self.width.unwrap_or(f64::NAN);
+        let round = |d: f64| {
+            // the skin is a ring at the surface of the wire
+            let dc = self.rho / (f64::consts::PI * d * d / 4f64);
+            let ac = surface / (f64::consts::PI * d);
+            if skin_depth < d / 2f64 { ac.max(dc) } else { dc }
+        };
+        match self.kind {
+            // the shield has the skin at its inside
+            Some(Kind::Coax) => {
+                let outer = self.outer.unwrap_or(f64::NAN);
+                round(inner) + surface / (f64::consts::PI * outer)
+            }
+            Some(Kind::TwoWire) => 2f64 * round(inner),
+            // the strip with its skin on both sides, the ground plane is left out
+            _ => {
+                let dc = self.rho / (width * self.thickness);
+                let ac = surface / (2f64 * width);
+                if 2f64 * skin_depth < self.thickness { ac.max(dc) } else { dc }
+            }
+        }
+    }
+    fn print_res(&self, z0: f64, e_eff: f64) {
+        let kind = if let Some(kind) = self.kind { kind.name() } else { "" };
+        let velocity = 1f64 / e_eff.sqrt();
+        let delay = e_eff.sqrt() / C0; //s/m
+        println!("the {} has the characteristic impedance: {}", kind, format_value(z0, "Ω"));
+        println!(
+            "εeff = {:.3}, the velocity is {:.3} c and the delay {}/m",
+            e_eff,
+            velocity,
+            format_value(delay, "s")
+        );
+        println!(
+            "per m: L' = {}/m and C' = {}/m",
+            format_value(z0 * delay, "H"),
+            format_value(delay / z0, "F")
+        );
+        if let Some(length) = self.length {
+            println!(
+                "over {} the delay is: {}",
+                format_value(length, "m"),
+                format_value(delay * length, "s")
+            );
+        }
+        if let Some(frequency) = self.frequency {
+            let resistance = self.resistance(frequency);
+            // alpha = R' / (2 Z0) for the conductor and pi f sqrt(eeff) tan(delta) / c0 for the
+            // dielectric, both in Np/m
+            let conductor = resistance / (2f64 * z0) * NEPER;
+            let dielectric =
+                f64::consts::PI * frequency * e_eff.sqrt() * self.tan_delta / C0 * NEPER;
+            println!(
+                "at {} the skin depth is {} and R' = {}/m",
+                format_value(frequency, "Hz"),
+                format_value((self.rho / (f64::consts::PI * frequency * MU0)).sqrt(), "m"),
+                format_value(resistance, "Ω")
+            );
+            println!(
+                "the loss is: {:.3} dB/100m of the conductor + {:.3} dB/100m of the dielectric \
+                 = {:.3} dB/100m",
+                conductor * 100f64,
+                dielectric * 100f64,
+                (conductor + dielectric) * 100f64
+            );
+            if let Some(length) = self.length {
+                let loss = (conductor + dielectric) * length;
+                println!(
+                    "over {} the loss is: {:.3} dB, {:.1} % of the power arrives",
+                    format_value(length, "m"),
+                    loss,
+                    100f64 * 10f64.powf(-loss / 10f64)
+                );
+            }
+        }
+        // the formulas of IPC-2141 are only good for some ratios
+        let width = self.width.unwrap_or_default();
+        let height = self.height.unwrap_or_default();
+        if self.kind == Some(Kind::Microstrip) && !(0.1f64..=2f64).contains(&(width / height)) {
+            println!("note: the microstrip formula is only good for 0.1 < w/h < 2");
+        }
+        if self.kind == Some(Kind::Stripline) && width / height > 0.35f64 {
+            println!("note: the stripline formula is only good for w/b < 0.35");
+        }
+        // the decibels of the mismatch to the usual 50 Ω
+        if (z0 - 50f64).abs() > 0.5f64 {
+            let reflection = ((z0 - 50f64) / (z0 + 50f64)).abs();
+            println!(
+                "at 50 Ω the reflection is {:.3}, a return loss of {:.1} dB",
+                reflection,
+                -decibel::voltage_db(reflection)
+            );
+        }
+    }
+}
+
+// rho in ohm*m of a material Elect knows
+fn material_rho(name: &str) -> Result<f64, &'static str> {
+    let elect = Elect::new(vec![name.to_string()])?;
+    match elect.rho {
+        Some(rho) => Ok(rho * 1e-6f64),
+        None => Err("material not found"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::calculated;
+
+    fn line(given: &[&str]) -> Result<Line, &'static str> {
+        calculated(given, Line::new, Line::calc)
+    }
+
+    #[test]
+    fn coax() {
+        // RG58 with polyethylene is close to 50 Ω
+        let rg58 = line(&["coax", "di=0.9mm", "da=2.95mm"]).unwrap();
+        let z0 = 60f64 / 2.25f64.sqrt() * (2.95f64 / 0.9f64).ln();
+        assert!((rg58.z0.unwrap() - z0).abs() < 1e-9);
+        assert!((rg58.z0.unwrap() - 47.49f64).abs() < 0.01f64);
+        assert_eq!(rg58.e_eff, Some(2.25f64));
+        let air = line(&["coax", "di=1mm", "da=2.3mm", "er=1"]).unwrap();
+        assert!((air.z0.unwrap() - 60f64 * 2.3f64.ln()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn two_wire_and_strips() {
+        let pair = line(&["pair", "d=0.5mm", "s=2mm"]).unwrap();
+        assert!((pair.z0.unwrap() - 120f64 / 2f64.sqrt() * 4f64.acosh()).abs() < 1e-9);
+        // 3 mm on 1.6 mm FR4 is the usual 50 Ω microstrip
+        let microstrip = line(&["microstrip", "w=3mm", "height=1.6mm"]).unwrap();
+        assert!((microstrip.z0.unwrap() - 49.40f64).abs() < 0.01f64);
+        let e_eff = microstrip.e_eff.unwrap();
+        assert!(e_eff > 1f64 && e_eff < 4.4f64);
+        let stripline = line(&["stripline", "w=0.2mm", "b=0.5mm"]).unwrap();
+        assert_eq!(stripline.e_eff, Some(4.4f64));
+        assert!(stripline.z0.unwrap() > 0f64);
+    }
+
+    #[test]
+    fn errors() {
+        assert!(line(&["d=1mm", "da=3mm"]).is_err());
+        assert!(line(&["coax", "di=1mm"]).is_err());
+        assert!(line(&["coax", "di=3mm", "da=1mm"]).is_err());
+        assert!(line(&["pair", "d=2mm", "s=1mm"]).is_err());
+        assert!(line(&["coax", "di=1mm", "da=3mm", "er=0.5"]).is_err());
+        assert!(line(&["microstrip", "w=10mm", "height=0.1mm"]).is_err());
+        assert!(line(&["coax", "di=1mm", "da=3mm", "unobtainium"]).is_err());
+    }
+
+    #[test]
+    fn skin_effect() {
+        // copper at 1 MHz has a skin depth of about 66 µm, much less than the wire
+        let coax = line(&["coax", "di=1mm", "da=3mm", "copper"]).unwrap();
+        let depth = (coax.rho / (f64::consts::PI * 1e6f64 * MU0)).sqrt();
+        assert!((depth - 66e-6f64).abs() < 1e-6f64);
+        assert!(coax.resistance(1e6f64) > coax.resistance(1e3f64));
+    }
+}